@@ -1,5 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use hyphenation::{Hyphenator, Load};
+use hypher::builder::TrieBuilder;
+use hypher::Trie;
 
 fn criterion_benchmark(c: &mut Criterion) {
     let english = "extensive";
@@ -34,6 +36,30 @@ fn criterion_benchmark(c: &mut Criterion) {
     bench(c, "hyphenation-load-greek", || {
         hyphenation::Standard::from_embedded(black_box(hyphenation::Language::GreekMono))
     });
+
+    // Compare tries whose transitions are labelled with bytes and chars.
+    let russian = "достопримечательность";
+    let bytes = build_russian(false);
+    let chars = build_russian(true);
+    let bytes = Trie::from_bytes(&bytes).unwrap();
+    let chars = Trie::from_bytes(&chars).unwrap();
+
+    bench(c, "hypher-russian-bytes", || {
+        drain(black_box(bytes).hyphenate(black_box(russian)))
+    });
+    bench(c, "hypher-russian-chars", || {
+        drain(black_box(chars).hyphenate(black_box(russian)))
+    });
+}
+
+fn build_russian(chars: bool) -> Vec<u8> {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../patterns/hyph-ru.tex");
+    let tex = std::fs::read_to_string(path).unwrap();
+    let mut builder = TrieBuilder::new();
+    builder.insert_tex(&tex).unwrap();
+    builder.set_chars(chars);
+    builder.compress();
+    builder.encode().unwrap()
 }
 
 fn bench<R>(c: &mut Criterion, name: &str, f: impl FnMut() -> R + Copy) {
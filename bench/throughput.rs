@@ -0,0 +1,61 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use hypher::{HyphenateExt, Lang, Trie};
+
+/// Realistic English prose to take the words from.
+const TEXT: &str = include_str!("../LICENSE-APACHE");
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let words: Vec<&str> = TEXT
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let bytes = Lang::English.trie().as_bytes();
+
+    let mut group = c.benchmark_group("english-words");
+    group.throughput(Throughput::Elements(words.len() as u64));
+
+    group.bench_function("hyphenate", |b| {
+        b.iter(|| {
+            for &word in black_box(&words) {
+                drain(hypher::hyphenate(word, Lang::English));
+            }
+        })
+    });
+
+    // Load the trie again for each word.
+    group.bench_function("cold-decode", |b| {
+        b.iter(|| {
+            for &word in black_box(&words) {
+                let trie = Trie::from_bytes(black_box(bytes)).unwrap();
+                drain(trie.hyphenate(word));
+            }
+        })
+    });
+
+    // Decode the root once and reuse it for all words.
+    group.bench_function("warm-reuse", |b| {
+        b.iter(|| {
+            for syllables in black_box(&words).iter().hyphenate(Lang::English) {
+                drain(syllables);
+            }
+        })
+    });
+
+    group.finish();
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../patterns/hyph-en-us.tex");
+    let tex = std::fs::read_to_string(path).unwrap();
+    let mut group = c.benchmark_group("build");
+    group.sample_size(10);
+    group.bench_function("english", |b| {
+        b.iter(|| hypher::builder::build_trie(black_box(&tex)).unwrap())
+    });
+    group.finish();
+}
+
+fn drain<T>(iter: impl IntoIterator<Item = T>) {
+    for _ in iter {}
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,14 @@
+//! Decompiles, measures and verifies arbitrary bytes as a trie.
+#![no_main]
+
+use hypher::builder::{dump_trie, trie_stats, TrieBuilder};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: &[u8]| {
+    let _ = trie_stats(bytes);
+    let _ = TrieBuilder::new().verify(bytes);
+    if let Ok(tex) = dump_trie(bytes) {
+        let mut builder = TrieBuilder::new();
+        let _ = builder.insert_tex(&tex);
+    }
+});
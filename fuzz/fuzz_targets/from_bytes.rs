@@ -0,0 +1,12 @@
+//! Loads arbitrary bytes as a trie and hyphenates arbitrary words with it.
+#![no_main]
+
+use hypher::{hyphenate, Lang};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (&[u8], &str)| {
+    let (bytes, word) = input;
+    if let Ok(lang) = Lang::from_bytes(bytes) {
+        assert_eq!(hyphenate(word, lang).collect::<String>(), word);
+    }
+});
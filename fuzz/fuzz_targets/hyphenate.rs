@@ -0,0 +1,15 @@
+//! Hyphenates arbitrary words with all built-in languages.
+#![no_main]
+
+use hypher::{hyphenate, hyphenate_bounded, hyphenate_positions, Lang};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (&str, u8, u8)| {
+    let (word, left_min, right_min) = input;
+    for &lang in Lang::all() {
+        assert_eq!(hyphenate(word, lang).collect::<String>(), word);
+        assert!(hyphenate_positions(word, lang).all(|i| word.is_char_boundary(i)));
+        let bounded = hyphenate_bounded(word, lang, left_min.into(), right_min.into());
+        assert_eq!(bounded.collect::<String>(), word);
+    }
+});
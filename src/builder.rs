@@ -1,76 +1,1091 @@
-///! This module implements compilation of a trie from a pattern file.
-use std::collections::HashMap;
+//! This module implements compilation of a trie from a pattern file.
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::str;
+
+use crate::{check_header, stored_bounds, State, TrieError};
 
 /// Generate an encoded tree from a source file.
-pub fn build_trie(tex: &str) -> Vec<u8> {
+///
+/// The (left,right)-hyphenmin stored in the trie is taken from the
+/// `\lefthyphenmin` and `\righthyphenmin` assignments in the file and
+/// defaults to `(2, 3)` if there are none.
+///
+/// If a pattern or exception is listed again with different levels, the later
+/// one silently wins. To find out about such [conflicts](Conflict), build the
+/// trie with a [`TrieBuilder`] and check [`TrieBuilder::conflicts`].
+pub fn build_trie(tex: &str) -> Result<Vec<u8>, BuildError> {
+    build(tex, None)
+}
+
+/// Generate an encoded tree from a source file, storing the given
+/// (left,right)-hyphenmin in it.
+///
+/// The hyphenmin assignments in the file are ignored. Conflicts are dropped
+/// like with [`build_trie`].
+pub fn build_trie_with(
+    tex: &str,
+    left_min: usize,
+    right_min: usize,
+) -> Result<Vec<u8>, BuildError> {
+    build(tex, Some((left_min, right_min)))
+}
+
+/// Generate an encoded tree, possibly overriding the file's hyphenmin.
+fn build(tex: &str, bounds: Option<(usize, usize)>) -> Result<Vec<u8>, BuildError> {
+    let mut builder = TrieBuilder::new();
+    builder.insert_tex(tex)?;
+    if let Some(bounds) = bounds {
+        builder.bounds = bounds;
+    }
+    builder.compress();
+    builder.encode()
+}
+
+/// Read a pattern file from disk and generate an encoded tree from it.
+///
+/// See [`read_tex_file`] for how the file is read and
+/// [`TrieBuilder::insert_tex_file`] for how `\input` commands are resolved.
+/// Conflicts are dropped like with [`build_trie`].
+pub fn build_trie_from_file(path: &Path) -> Result<Vec<u8>, BuildError> {
+    let mut builder = TrieBuilder::new();
+    builder.insert_tex_file(path)?;
+    builder.compress();
+    builder.encode()
+}
+
+/// Read a pattern file from disk.
+///
+/// With the `compress` feature, files ending in `.gz` or `.zst` are
+/// decompressed with gzip or zstd, respectively. Other files are read as is.
+///
+/// Fails with an [`io::ErrorKind::InvalidData`] error if the (decompressed)
+/// file is not valid UTF-8.
+pub fn read_tex_file(path: &Path) -> Result<String, BuildError> {
+    let bytes = decompress(path, fs::read(path)?)?;
+    let tex = String::from_utf8(bytes).map_err(|err| {
+        let offset = err.utf8_error().valid_up_to();
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} is not valid UTF-8 (at byte {offset})", path.display()),
+        )
+    })?;
+    Ok(tex)
+}
+
+/// Read a pattern file and, recursively, all files it includes with `\input`.
+///
+/// Returns the contents of each file once for each time it is included,
+/// starting with the file at `path`. Includes are resolved and read like with
+/// [`TrieBuilder::insert_tex_file`] and fail the same way, but the patterns
+/// are not checked. This lets tools look at the same patterns and exceptions
+/// as the builder.
+pub fn read_tex_files(path: &Path) -> Result<Vec<String>, BuildError> {
+    let mut files = vec![];
+    load_tex_file(path, &mut vec![], &mut files, false)?;
+    Ok(files)
+}
+
+/// Merge several encoded tries into one.
+///
+/// If multiple tries contain a pattern or exception with the same letters, the
+/// one from the later trie wins. The hyphenmins are taken from the last trie.
+pub fn merge_tries(tries: &[&[u8]]) -> Result<Vec<u8>, BuildError> {
     let mut builder = TrieBuilder::new();
-    parse(tex, |pat| builder.insert(pat));
+    for trie in tries {
+        builder.insert_tex(&dump_trie(trie)?)?;
+    }
     builder.compress();
     builder.encode()
 }
 
+/// Decompress the contents of a file based on its extension.
+#[cfg(feature = "compress")]
+fn decompress(path: &Path, bytes: Vec<u8>) -> io::Result<Vec<u8>> {
+    use std::io::Read;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("gz") => {
+            let mut out = vec![];
+            flate2::read::GzDecoder::new(bytes.as_slice()).read_to_end(&mut out)?;
+            Ok(out)
+        }
+        Some("zst") => zstd::decode_all(bytes.as_slice()),
+        _ => Ok(bytes),
+    }
+}
+
+/// Without the `compress` feature, files are never decompressed.
+#[cfg(not(feature = "compress"))]
+fn decompress(_: &Path, bytes: Vec<u8>) -> io::Result<Vec<u8>> {
+    Ok(bytes)
+}
+
+/// Decompile an encoded trie back into a TeX pattern file.
+///
+/// The output contains the stored hyphenmins, a `\patterns{...}` block and,
+/// if there are any exceptions, a `\hyphenation{...}` block. Entries are
+/// sorted by the bytes of their letters, which is also the order in which the
+/// encoded trie is walked. Building the output again thus yields the same trie
+/// as building the original patterns in that order.
+pub fn dump_trie(trie: &[u8]) -> Result<String, TrieError> {
+    check_header(trie)?;
+    let (left_min, right_min) = stored_bounds(trie);
+
+    let mut patterns = vec![];
+    let mut exceptions = vec![];
+    let mut path = vec![];
+    let root = State::root(trie).ok_or(TrieError::Malformed)?;
+    walk(root, &mut path, &mut |path, state| {
+        if path.first() == Some(&crate::EXCEPTION) {
+            exceptions.push(crate::decompile(path, state.levels(), true));
+        } else {
+            patterns.push(crate::decompile(path, state.levels(), false));
+        }
+    })?;
+
+    let mut tex = format!("\\lefthyphenmin={left_min}\n\\righthyphenmin={right_min}\n");
+    tex.push_str("\\patterns{\n");
+    for pattern in patterns {
+        tex.push_str(&pattern);
+        tex.push('\n');
+    }
+    tex.push_str("}\n");
+
+    if !exceptions.is_empty() {
+        tex.push_str("\\hyphenation{\n");
+        for word in exceptions {
+            tex.push_str(&word);
+            tex.push('\n');
+        }
+        tex.push_str("}\n");
+    }
+
+    Ok(tex)
+}
+
+/// Statistics about an encoded trie.
+///
+/// This struct is created by [`trie_stats`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TrieStats {
+    /// The version of the format the trie is encoded in.
+    pub version: u8,
+    /// The number of distinct nodes, with shared subtrees counted once.
+    pub nodes: usize,
+    /// The total number of transitions of all nodes.
+    pub transitions: usize,
+    /// The length of the levels table in bytes.
+    pub levels: usize,
+    /// The size of the whole encoded trie in bytes.
+    pub size: usize,
+    /// The stored left-hyphenmin.
+    pub left_min: usize,
+    /// The stored right-hyphenmin.
+    pub right_min: usize,
+    /// Whether the trie ends with a checksum.
+    pub checksum: bool,
+}
+
+/// Compute statistics about an encoded trie.
+pub fn trie_stats(trie: &[u8]) -> Result<TrieStats, TrieError> {
+    check_header(trie)?;
+    let (left_min, right_min) = stored_bounds(trie);
+
+    // Visit every node once. The levels table ends where the first node
+    // starts.
+    let root = State::root(trie).ok_or(TrieError::Malformed)?;
+    let mut seen = HashSet::new();
+    let mut stack = vec![root];
+    let mut first = root.addr;
+    let mut transitions = 0;
+    while let Some(state) = stack.pop() {
+        if !seen.insert(state.addr) {
+            continue;
+        }
+        first = first.min(state.addr);
+        for label in state.labels() {
+            transitions += 1;
+            stack.push(state.transition(label).ok_or(TrieError::Malformed)?);
+        }
+    }
+
+    Ok(TrieStats {
+        version: trie[crate::MAGIC.len()],
+        nodes: seen.len(),
+        transitions,
+        levels: first.checked_sub(4).ok_or(TrieError::Malformed)?,
+        size: trie.len(),
+        left_min,
+        right_min,
+        checksum: crate::flags(trie) & crate::FLAG_CHECKSUM != 0,
+    })
+}
+
+/// Generate an encoded tree from a source file and compute its statistics.
+///
+/// This works like [`build_trie`] followed by [`trie_stats`] and is handy to
+/// catch size regressions of a pattern file in a test.
+///
+/// # Example
+/// ```
+/// use hypher::builder::build_and_measure;
+///
+/// let (trie, stats) = build_and_measure(r"\patterns{ 1b ab1c }").unwrap();
+/// assert_eq!(stats.size, trie.len());
+/// assert!(stats.size < 64, "trie has grown to {} bytes", stats.size);
+/// ```
+pub fn build_and_measure(tex: &str) -> Result<(Vec<u8>, TrieStats), BuildError> {
+    let trie = build_trie(tex)?;
+    let stats = trie_stats(&trie)?;
+    Ok((trie, stats))
+}
+
+/// Visit the paths to all final states below `state` in byte order.
+///
+/// Nodes shared through suffix compression are visited once per path leading
+/// to them. Fails with [`TrieError::Malformed`] if a transition leads back to
+/// a node on the current path or if the trie would yield far more paths than
+/// it could hold, as can happen in a corrupted trie.
+fn walk<'a, F>(state: State<'a>, path: &mut Vec<u8>, f: &mut F) -> Result<(), TrieError>
+where
+    F: FnMut(&[u8], State<'a>),
+{
+    // Real tries yield fewer bytes of paths than ten times their size, but
+    // shared nodes let a corrupted one yield exponentially many.
+    let mut budget = state.data.len().saturating_mul(256);
+    walk_from(state, path, &mut vec![], &mut budget, f)
+}
+
+/// Visit the paths to all final states below `state` like [`walk`], given the
+/// addresses of the nodes on the path to it and how many more nodes and bytes
+/// of paths may be visited.
+fn walk_from<'a, F>(
+    state: State<'a>,
+    path: &mut Vec<u8>,
+    ancestors: &mut Vec<usize>,
+    budget: &mut usize,
+    f: &mut F,
+) -> Result<(), TrieError>
+where
+    F: FnMut(&[u8], State<'a>),
+{
+    if ancestors.contains(&state.addr) {
+        return Err(TrieError::Malformed);
+    }
+    ancestors.push(state.addr);
+
+    let cost = if state.is_final() { 1 + path.len() } else { 1 };
+    *budget = budget.checked_sub(cost).ok_or(TrieError::Malformed)?;
+
+    if state.is_final() {
+        f(path, state);
+    }
+
+    // Code points sort like their UTF-8 encoding and the exception label
+    // sorts last like the exception byte.
+    let mut labels: Vec<u32> = state.labels().collect();
+    labels.sort_unstable();
+    for label in labels {
+        let len = path.len();
+        if state.width == 1 {
+            path.push(label as u8);
+        } else if label == crate::EXCEPTION_CHAR {
+            path.push(crate::EXCEPTION);
+        } else {
+            let c = char::from_u32(label).ok_or(TrieError::Malformed)?;
+            path.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        }
+        let next = state.transition(label).ok_or(TrieError::Malformed)?;
+        walk_from(next, path, ancestors, budget, f)?;
+        path.truncate(len);
+    }
+
+    ancestors.pop();
+    Ok(())
+}
+
+/// The number of nodes of a trie before and after compressing it.
+///
+/// This struct is returned by [`compress`](TrieBuilder::compress) and
+/// [`compress2`](TrieBuilder::compress2).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Compression {
+    /// The number of nodes before compression.
+    pub before: usize,
+    /// The number of nodes after compression.
+    pub after: usize,
+}
+
+/// Two patterns or exceptions with the same letters but different levels.
+///
+/// These are collected by a [`TrieBuilder`] and returned by
+/// [`conflicts`](TrieBuilder::conflicts).
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Conflict {
+    /// The pattern or exception that was inserted first.
+    pub old: String,
+    /// The pattern or exception that replaced it.
+    pub new: String,
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} replaces {} with different levels", self.new, self.old)
+    }
+}
+
+/// The highest level a pattern can have.
+pub const MAX_LEVEL: u8 = 9;
+
+/// The maximum distance in bytes between two levels of a pattern, or between
+/// the start of the pattern and its first level.
+///
+/// Each level is encoded in a single byte as `distance * 10 + level`.
+pub const MAX_LEVEL_DISTANCE: usize = 24;
+
+/// The maximum number of levels a single pattern can have.
+pub const MAX_LEVELS_PER_NODE: usize = 15;
+
+/// The maximum offset of the levels of a node into the encoded trie.
+///
+/// Since levels are shared between patterns where possible, this limits the
+/// number of distinct level sequences rather than the number of patterns.
+pub const MAX_LEVEL_OFFSET: usize = 4095;
+
+/// The maximum number of transitions of a node.
+pub const MAX_TRANSITIONS: usize = 255;
+
+/// The maximum length of the metadata of a trie in bytes.
+pub const MAX_METADATA_LEN: usize = 65535;
+
+/// An error that can occur while building a trie.
+#[derive(Debug)]
+pub enum BuildError {
+    /// The pattern file could not be read.
+    Io(io::Error),
+    /// A trie to merge could not be decoded.
+    Trie(TrieError),
+    /// Two levels of a pattern are more than [`MAX_LEVEL_DISTANCE`] bytes
+    /// apart.
+    LevelDistanceTooLarge {
+        /// The index of the level in the levels table.
+        index: usize,
+        /// The distance to the previous level.
+        dist: usize,
+    },
+    /// The levels of a node start after [`MAX_LEVEL_OFFSET`].
+    LevelOffsetTooLarge {
+        /// The index of the node.
+        node: usize,
+        /// The offset of the node's levels into the encoded trie.
+        offset: usize,
+    },
+    /// A node has more than [`MAX_LEVELS_PER_NODE`] levels.
+    TooManyLevels {
+        /// The index of the node.
+        node: usize,
+        /// The number of levels.
+        count: usize,
+    },
+    /// A node has more than [`MAX_TRANSITIONS`] transitions.
+    TooManyTransitions {
+        /// The index of the node.
+        node: usize,
+        /// The number of transitions.
+        count: usize,
+    },
+    /// A pattern in the pattern file has a level that is not a single digit,
+    /// i.e. larger than [`MAX_LEVEL`].
+    LevelOutOfRange {
+        /// The line of the level in the file, starting at 1.
+        line: usize,
+        /// The column of the level in the file, in chars and starting at 1.
+        column: usize,
+        /// The digits of the level.
+        level: String,
+    },
+    /// A pattern in the pattern file has a level more than
+    /// [`MAX_LEVEL_DISTANCE`] bytes after the previous one or its start.
+    PatternDistanceTooLarge {
+        /// The line of the level in the file, starting at 1.
+        line: usize,
+        /// The column of the level in the file, in chars and starting at 1.
+        column: usize,
+        /// The pattern.
+        pattern: String,
+        /// The distance to the previous level.
+        dist: usize,
+    },
+    /// A hyphenmin is too large to be stored.
+    BoundTooLarge(usize),
+    /// A node or transition target is too far away to be addressed.
+    AddressOutOfRange {
+        /// The index of the node.
+        node: usize,
+        /// The address or address delta that was out of range.
+        value: isize,
+    },
+    /// A pattern file includes itself through a chain of `\input` commands.
+    InputCycle(PathBuf),
+    /// A file included with `\input` could not be read or has a level out of
+    /// range.
+    Input {
+        /// The path of the included file.
+        path: PathBuf,
+        /// The error in the included file.
+        error: Box<BuildError>,
+    },
+    /// The metadata is longer than [`MAX_METADATA_LEN`] bytes.
+    MetadataTooLong(usize),
+    /// A pattern or exception is missing from an encoded trie or has
+    /// different levels there, as found by [`TrieBuilder::verify`].
+    Mismatch(String),
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => err.fmt(f),
+            Self::Trie(err) => err.fmt(f),
+            Self::LevelDistanceTooLarge { index, dist } => write!(
+                f,
+                "level {index} is at distance {dist} from the previous one \
+                 (max {MAX_LEVEL_DISTANCE})"
+            ),
+            Self::LevelOffsetTooLarge { node, offset } => write!(
+                f,
+                "levels of node {node} start at too high offset {offset} \
+                 (max {MAX_LEVEL_OFFSET})"
+            ),
+            Self::TooManyLevels { node, count } => write!(
+                f,
+                "node {node} has too many levels ({count}, max {MAX_LEVELS_PER_NODE})"
+            ),
+            Self::TooManyTransitions { node, count } => write!(
+                f,
+                "node {node} has too many transitions ({count}, max {MAX_TRANSITIONS})"
+            ),
+            Self::LevelOutOfRange { line, column, level } => {
+                write!(f, "line {line}, column {column}: level {level} out of range")
+            }
+            Self::PatternDistanceTooLarge { line, column, pattern, dist } => write!(
+                f,
+                "line {line}, column {column}: pattern '{pattern}' has inter-letter \
+                 distance {dist} which exceeds max {MAX_LEVEL_DISTANCE}"
+            ),
+            Self::BoundTooLarge(bound) => write!(f, "hyphenmin {bound} is too large"),
+            Self::AddressOutOfRange { node, value } => {
+                write!(f, "address {value} of node {node} is out of range")
+            }
+            Self::InputCycle(path) => {
+                write!(f, "{} includes itself with \\input", path.display())
+            }
+            Self::Input { path, error } => write!(f, "in {}: {error}", path.display()),
+            Self::MetadataTooLong(len) => {
+                write!(f, "metadata is too long ({len} bytes, max {MAX_METADATA_LEN})")
+            }
+            Self::Mismatch(pattern) => {
+                write!(f, "{pattern} does not round-trip through the encoded trie")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Trie(err) => Some(err),
+            Self::Input { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BuildError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<TrieError> for BuildError {
+    fn from(err: TrieError) -> Self {
+        Self::Trie(err)
+    }
+}
+
 /// Parse a TeX pattern file, calling `f` with each pattern.
-pub fn parse<F>(tex: &str, mut f: F)
+///
+/// See [`parse_reader`] to parse a file without reading it into memory
+/// first.
+pub fn parse<F>(tex: &str, f: F)
 where
     F: FnMut(&str),
 {
-    let mut s = Scanner(tex);
-    while let Some(c) = s.eat() {
-        match c {
-            '%' => {
-                s.eat_while(|c| c != '\n');
-            }
-            '\\' if s.eat_if("patterns{") => loop {
-                let pat = s.eat_while(|c| c != '}' && c != '%' && !c.is_whitespace());
-                if !pat.is_empty() {
-                    f(pat);
+    parse_reader(tex.as_bytes(), f).expect("reading a string cannot fail");
+}
+
+/// Parse a TeX pattern file from a reader, calling `f` with each pattern.
+///
+/// The input is scanned incrementally, so only the current chunk and the
+/// word being read are held in memory. Reading stops at `\endinput`.
+///
+/// Fails if reading fails or with an [`io::ErrorKind::InvalidData`] error if
+/// the input is not valid UTF-8. Patterns before the error are still passed
+/// to `f`.
+pub fn parse_reader<R, F>(mut reader: R, mut f: F) -> io::Result<()>
+where
+    R: BufRead,
+    F: FnMut(&str),
+{
+    let mut scanner = Scanner::new();
+    let mut emit = |entry: Entry, _| {
+        if let Entry::Pattern(pat) = entry {
+            f(pat);
+        }
+    };
+
+    // The first bytes of a char that straddles two chunks.
+    let mut partial = Vec::with_capacity(4);
+    let invalid = |offset: usize| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("input is not valid UTF-8 (at byte {offset})"),
+        )
+    };
+
+    loop {
+        let chunk = match reader.fill_buf() {
+            Ok(chunk) => chunk,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        if chunk.is_empty() {
+            break;
+        }
+
+        // Complete the straddling char first.
+        let len = chunk.len();
+        let mut bytes = chunk;
+        if let Some(&lead) = partial.first() {
+            let need = utf8_width(lead) - partial.len();
+            let take = need.min(bytes.len());
+            partial.extend_from_slice(&bytes[..take]);
+            bytes = &bytes[take..];
+            if take == need {
+                let c = str::from_utf8(&partial).map_err(|_| invalid(scanner.offset))?;
+                scanner.feed_str(c, &mut emit);
+                partial.clear();
+            }
+        }
+
+        match str::from_utf8(bytes) {
+            Ok(s) => scanner.feed_str(s, &mut emit),
+            Err(err) => {
+                let (valid, rest) = bytes.split_at(err.valid_up_to());
+                scanner.feed_str(str::from_utf8(valid).unwrap(), &mut emit);
+                if err.error_len().is_some() && !scanner.is_done() {
+                    return Err(invalid(scanner.offset));
                 }
-                match s.eat() {
-                    Some('}') => break,
-                    Some('%') => s.eat_while(|c| c != '\n'),
-                    _ => s.eat_while(char::is_whitespace),
-                };
-            },
-            _ => {}
+                partial.extend_from_slice(rest);
+            }
+        }
+
+        reader.consume(len);
+        if scanner.is_done() {
+            return Ok(());
         }
     }
+
+    if !partial.is_empty() {
+        return Err(invalid(scanner.offset));
+    }
+
+    scanner.finish(&mut emit);
+    Ok(())
+}
+
+/// The length of a UTF-8 encoded char in bytes, given its first byte.
+fn utf8_width(lead: u8) -> usize {
+    match lead.leading_ones() {
+        0 => 1,
+        n => n as usize,
+    }
+}
+
+/// Parse a TeX pattern file, calling `f` with each hyphenation exception.
+///
+/// Exceptions are words like `as-so-ciate` listed in a `\hyphenation{...}`
+/// block, where the hyphens mark the only permitted breaks.
+pub fn parse_exceptions<F>(tex: &str, mut f: F)
+where
+    F: FnMut(&str),
+{
+    scan(tex, |entry, _| {
+        if let Entry::Exception(word) = entry {
+            f(word);
+        }
+    });
 }
 
-struct Scanner<'a>(&'a str);
+/// An entry in a TeX pattern file.
+enum Entry<'a> {
+    /// A pattern like `.a1bc2d` from a `\patterns{...}` block.
+    Pattern(&'a str),
+    /// A word like `as-so-ciate` from a `\hyphenation{...}` block.
+    Exception(&'a str),
+    /// The value of a `\lefthyphenmin` assignment.
+    LeftMin(usize),
+    /// The value of a `\righthyphenmin` assignment.
+    RightMin(usize),
+    /// The path of an `\input` command.
+    Input(&'a str),
+}
 
-impl<'a> Scanner<'a> {
-    fn eat(&mut self) -> Option<char> {
-        let mut chars = self.0.chars();
-        let c = chars.next();
-        self.0 = chars.as_str();
-        c
+/// Scan a TeX pattern file, calling `f` with each entry and the byte offset
+/// at which it starts.
+fn scan<F>(tex: &str, mut f: F)
+where
+    F: FnMut(Entry, usize),
+{
+    let mut scanner = Scanner::new();
+    scanner.feed_str(tex, &mut f);
+    scanner.finish(&mut f);
+}
+
+/// The line and column of a byte offset into a file, both starting at 1.
+///
+/// The column is counted in chars, not including a byte order mark at the
+/// start of the file, which is skipped like when parsing.
+fn line_column(tex: &str, offset: usize) -> (usize, usize) {
+    let before = &tex[..offset];
+    let before = before.strip_prefix('\u{FEFF}').unwrap_or(before);
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, column)
+}
+
+/// Check that all levels of a pattern starting at byte `offset` of the file
+/// are single digits and close enough to each other to be encoded.
+fn check_levels(tex: &str, pattern: &str, offset: usize) -> Result<(), BuildError> {
+    let mut rest = pattern;
+    let mut dist = 0;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        let digits = &rest[start..];
+        let len = digits.find(|c: char| !c.is_ascii_digit()).unwrap_or(digits.len());
+        let index = pattern.len() - digits.len();
+        if len > 1 {
+            let (line, column) = line_column(tex, offset + index);
+            let level = digits[..len].to_string();
+            return Err(BuildError::LevelOutOfRange { line, column, level });
+        }
+
+        // Like in `TrieBuilder::insert`, the distance is counted in bytes and
+        // zero levels are dropped.
+        dist += start;
+        if !digits.starts_with('0') {
+            if dist > MAX_LEVEL_DISTANCE {
+                let (line, column) = line_column(tex, offset + index);
+                let pattern = pattern.to_string();
+                return Err(BuildError::PatternDistanceTooLarge {
+                    line,
+                    column,
+                    pattern,
+                    dist,
+                });
+            }
+            dist = 0;
+        }
+        rest = &digits[len..];
+    }
+    Ok(())
+}
+
+/// Read a pattern file and, recursively, the files it includes with `\input`
+/// into `files`, checking the levels of all patterns if `check` is set.
+///
+/// The canonical paths of the files that are currently being loaded are kept
+/// in `stack` to detect cycles.
+fn load_tex_file(
+    path: &Path,
+    stack: &mut Vec<PathBuf>,
+    files: &mut Vec<String>,
+    check: bool,
+) -> Result<(), BuildError> {
+    let canonical = fs::canonicalize(path)?;
+    if stack.contains(&canonical) {
+        return Err(BuildError::InputCycle(path.to_path_buf()));
     }
 
-    fn eat_if(&mut self, pat: &str) -> bool {
-        let matches = self.0.starts_with(pat);
-        if matches {
-            self.0 = &self.0[pat.len()..];
+    let tex = read_tex_file(path)?;
+    let mut result = Ok(());
+    let mut inputs = vec![];
+    scan(&tex, |entry, offset| match (entry, &result) {
+        (Entry::Pattern(pat), Ok(())) if check => {
+            result = check_levels(&tex, pat, offset)
         }
-        matches
+        (Entry::Input(input), _) => inputs.push(resolve_input(path, input)),
+        _ => {}
+    });
+    result?;
+
+    files.push(tex);
+    stack.push(canonical);
+    for input in inputs {
+        load_tex_file(&input, stack, files, check).map_err(|err| match err {
+            BuildError::InputCycle(_) | BuildError::Input { .. } => err,
+            err => BuildError::Input { path: input, error: Box::new(err) },
+        })?;
     }
+    stack.pop();
+    Ok(())
+}
 
-    fn eat_while(&mut self, f: fn(char) -> bool) -> &'a str {
-        let mut offset = 0;
-        let mut chars = self.0.chars();
-        while chars.next().map_or(false, f) {
-            offset = self.0.len() - chars.as_str().len();
+/// Resolve the path of an `\input` command in the file at `path`.
+fn resolve_input(path: &Path, input: &str) -> PathBuf {
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let resolved = dir.join(input);
+    if resolved.extension().is_none() && !resolved.exists() {
+        resolved.with_extension("tex")
+    } else {
+        resolved
+    }
+}
+
+/// The commands of a pattern file and the modes they switch to.
+const COMMANDS: [(&str, Mode); 6] = [
+    ("endinput", Mode::EndInput),
+    ("input", Mode::Input { space: false }),
+    ("patterns", Mode::Open(Block::Patterns)),
+    ("hyphenation", Mode::Open(Block::Hyphenation)),
+    ("lefthyphenmin", Mode::Assignment { min: Min::Left, eq: false }),
+    ("righthyphenmin", Mode::Assignment { min: Min::Right, eq: false }),
+];
+
+/// Scans a pattern file one char at a time.
+///
+/// Since the state is kept between chars, the file can be fed in chunks that
+/// split commands, comments and words at arbitrary points.
+struct Scanner {
+    mode: Mode,
+    /// The word, command name or number being read.
+    carry: String,
+    /// The byte offset at which the contents of `carry` start.
+    start: usize,
+    /// The byte offset of the next char.
+    offset: usize,
+}
+
+/// What the scanner is currently reading.
+#[derive(Copy, Clone)]
+enum Mode {
+    /// Text outside of blocks, which is ignored.
+    Text,
+    /// A comment up to the end of the line, inside of a block or not.
+    Comment(Option<Block>),
+    /// The name of a command after a backslash. Commands that aren't known
+    /// are skipped.
+    Command,
+    /// Whitespace and an optional `=` before a hyphenmin value.
+    Assignment { min: Min, eq: bool },
+    /// The digits of a hyphenmin value.
+    Value(Min),
+    /// Right after `\patterns` or `\hyphenation` or in the whitespace
+    /// before the opening brace of the block.
+    Open(Block),
+    /// The whitespace-separated words of a block.
+    Block(Block),
+    /// Right after `\endinput`, which only takes effect without further
+    /// letters.
+    EndInput,
+    /// After `\endinput`, where the rest of the file is ignored.
+    End,
+    /// Right after `\input` or in the whitespace after it. Only followed by
+    /// whitespace or a brace, it is not part of a longer command name.
+    Input { space: bool },
+    /// The path of an `\input` command, either in braces or up to the next
+    /// whitespace.
+    InputPath { braced: bool },
+}
+
+/// A kind of block.
+#[derive(Copy, Clone)]
+enum Block {
+    Patterns,
+    Hyphenation,
+}
+
+/// A kind of hyphenmin.
+#[derive(Copy, Clone)]
+enum Min {
+    Left,
+    Right,
+}
+
+impl Scanner {
+    fn new() -> Self {
+        Self {
+            mode: Mode::Text,
+            carry: String::new(),
+            start: 0,
+            offset: 0,
+        }
+    }
+
+    /// Feed a chunk of the file.
+    fn feed_str<F>(&mut self, chunk: &str, f: &mut F)
+    where
+        F: FnMut(Entry, usize),
+    {
+        for c in chunk.chars() {
+            if self.is_done() {
+                break;
+            }
+            self.feed(c, f);
+            self.offset += c.len_utf8();
+        }
+    }
+
+    /// Whether the rest of the file is ignored.
+    fn is_done(&self) -> bool {
+        matches!(self.mode, Mode::End)
+    }
+
+    /// Feed the next char of the file.
+    fn feed<F>(&mut self, c: char, f: &mut F)
+    where
+        F: FnMut(Entry, usize),
+    {
+        // Skip the byte order mark some editors write.
+        if c == '\u{FEFF}' && self.offset == 0 {
+            return;
+        }
+
+        match self.mode {
+            Mode::Text => match c {
+                '%' => self.mode = Mode::Comment(None),
+                '\\' => self.mode = Mode::Command,
+                _ => {}
+            },
+            Mode::Comment(block) => {
+                if c == '\n' {
+                    self.mode = block.map_or(Mode::Text, Mode::Block);
+                }
+            }
+            Mode::Command if self.carry.is_empty() && !c.is_alphabetic() && c != '\\' => {
+                // A control symbol like `\%` or `\{`, which escapes the char
+                // instead of starting a comment or group. Another backslash
+                // can still start a command though.
+                self.mode = Mode::Text;
+            }
+            Mode::Command => {
+                self.carry.push(c);
+                let mut candidates = COMMANDS
+                    .iter()
+                    .filter(|(name, _)| name.starts_with(self.carry.as_str()));
+                match candidates.next() {
+                    Some(&(name, mode)) if name == self.carry => {
+                        self.carry.clear();
+                        self.mode = mode;
+                    }
+                    Some(_) => {}
+                    None => {
+                        // Not a command we know. The name so far consists of
+                        // letters, so only the current char can be relevant.
+                        self.carry.clear();
+                        self.mode = Mode::Text;
+                        self.feed(c, f);
+                    }
+                }
+            }
+            Mode::Assignment { min, eq } => {
+                if c == '=' && !eq {
+                    self.mode = Mode::Assignment { min, eq: true };
+                } else if c.is_ascii_digit() {
+                    self.start = self.offset;
+                    self.carry.push(c);
+                    self.mode = Mode::Value(min);
+                } else if !c.is_whitespace() {
+                    self.mode = Mode::Text;
+                    self.feed(c, f);
+                }
+            }
+            Mode::Value(min) => {
+                if c.is_ascii_digit() {
+                    self.carry.push(c);
+                } else {
+                    self.value(min, f);
+                    self.mode = Mode::Text;
+                    self.feed(c, f);
+                }
+            }
+            Mode::Open(block) => {
+                if c == '{' {
+                    self.mode = Mode::Block(block);
+                } else if c.is_alphabetic() {
+                    // Part of a longer command name.
+                    self.mode = Mode::Text;
+                } else if !c.is_whitespace() {
+                    self.mode = Mode::Text;
+                    self.feed(c, f);
+                }
+            }
+            Mode::Block(block) => match c {
+                '}' => {
+                    self.word(block, f);
+                    self.mode = Mode::Text;
+                }
+                '%' => {
+                    self.word(block, f);
+                    self.mode = Mode::Comment(Some(block));
+                }
+                c if c.is_whitespace() => self.word(block, f),
+                c => {
+                    if self.carry.is_empty() {
+                        self.start = self.offset;
+                    }
+                    self.carry.push(c);
+                }
+            },
+            Mode::EndInput => {
+                if c.is_alphabetic() {
+                    self.mode = Mode::Text;
+                } else {
+                    self.mode = Mode::End;
+                }
+            }
+            Mode::End => {}
+            Mode::Input { space } => {
+                if c == '{' {
+                    self.start = self.offset + 1;
+                    self.mode = Mode::InputPath { braced: true };
+                } else if c.is_whitespace() {
+                    self.mode = Mode::Input { space: true };
+                } else if !space || c == '%' || c == '\\' {
+                    self.mode = Mode::Text;
+                    self.feed(c, f);
+                } else {
+                    self.start = self.offset;
+                    self.carry.push(c);
+                    self.mode = Mode::InputPath { braced: false };
+                }
+            }
+            Mode::InputPath { braced } => {
+                let end = if braced { c == '}' } else { c.is_whitespace() || c == '%' };
+                if end {
+                    self.input(f);
+                    self.mode = Mode::Text;
+                    if !braced {
+                        self.feed(c, f);
+                    }
+                } else {
+                    self.carry.push(c);
+                }
+            }
+        }
+    }
+
+    /// Finish the file, emitting a word or value that runs up to its end.
+    fn finish<F>(&mut self, f: &mut F)
+    where
+        F: FnMut(Entry, usize),
+    {
+        match self.mode {
+            Mode::Value(min) => self.value(min, f),
+            Mode::Block(block) => self.word(block, f),
+            Mode::InputPath { braced: false } => self.input(f),
+            _ => {}
+        }
+        self.mode = Mode::Text;
+    }
+
+    /// Emit the `\input` path in `carry`, if any.
+    fn input<F>(&mut self, f: &mut F)
+    where
+        F: FnMut(Entry, usize),
+    {
+        if !self.carry.is_empty() {
+            f(Entry::Input(&self.carry), self.start);
+            self.carry.clear();
         }
-        let head = &self.0[..offset];
-        self.0 = &self.0[offset..];
-        head
+    }
+
+    /// Emit the word in `carry`, if any.
+    fn word<F>(&mut self, block: Block, f: &mut F)
+    where
+        F: FnMut(Entry, usize),
+    {
+        if !self.carry.is_empty() {
+            let entry = match block {
+                Block::Patterns => Entry::Pattern(&self.carry),
+                Block::Hyphenation => Entry::Exception(&self.carry),
+            };
+            f(entry, self.start);
+            self.carry.clear();
+        }
+    }
+
+    /// Emit the hyphenmin value in `carry`, if it fits into a `usize`.
+    fn value<F>(&mut self, min: Min, f: &mut F)
+    where
+        F: FnMut(Entry, usize),
+    {
+        if let Ok(value) = self.carry.parse() {
+            let entry = match min {
+                Min::Left => Entry::LeftMin(value),
+                Min::Right => Entry::RightMin(value),
+            };
+            f(entry, self.start);
+        }
+        self.carry.clear();
     }
 }
 
 /// Builds a trie from patterns.
-struct TrieBuilder {
+///
+/// This is what [`build_trie`] uses under the hood. Use it directly to insert
+/// patterns one at a time, for example when they are generated
+/// programmatically. First [insert](Self::insert) all patterns and
+/// [exceptions](Self::insert_exception), then optionally
+/// [compress](Self::compress) the trie and finally [encode](Self::encode) it.
+///
+/// # Example
+/// ```
+/// use hypher::builder::TrieBuilder;
+///
+/// let mut builder = TrieBuilder::new();
+/// builder.insert("1b");
+/// builder.insert("ab1c");
+/// builder.set_bounds(1, 1);
+/// builder.compress();
+/// let trie = builder.encode().unwrap();
+///
+/// # #[cfg(feature = "dyn")] {
+/// let lang = hypher::Lang::from_bytes(&trie).unwrap();
+/// let syllables = hypher::hyphenate("abcab", lang);
+/// assert_eq!(syllables.join("-"), "a-b-ca-b");
+/// # }
+/// ```
+pub struct TrieBuilder {
     root: usize,
     nodes: Vec<Node>,
     levels: Vec<(usize, u8)>,
+    bounds: (usize, usize),
+    checksum: bool,
+    chars: bool,
+    metadata: String,
+    compressed: bool,
+    conflicts: Vec<Conflict>,
+}
+
+impl Default for TrieBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// A node in the trie.
@@ -83,62 +1098,360 @@ struct Node {
 
 impl TrieBuilder {
     /// Create a new trie with just the root node.
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             root: 0,
             nodes: vec![Node::default()],
             levels: vec![],
+            bounds: (2, 3),
+            checksum: false,
+            chars: false,
+            metadata: String::new(),
+            compressed: false,
+            conflicts: vec![],
         }
     }
 
+    /// Set the (left,right)-hyphenmin to store in the trie.
+    ///
+    /// Defaults to `(2, 3)`.
+    pub fn set_bounds(&mut self, left_min: usize, right_min: usize) {
+        self.bounds = (left_min, right_min);
+    }
+
+    /// Set whether to append a CRC-32 checksum to the encoded trie.
+    ///
+    /// The checksum lets [`Lang::from_bytes_checked`](crate::Lang) detect
+    /// corrupted data. Defaults to `false`.
+    pub fn set_checksum(&mut self, checksum: bool) {
+        self.checksum = checksum;
+    }
+
+    /// Set whether to label the transitions of the encoded trie with whole
+    /// chars instead of bytes.
+    ///
+    /// Patterns are normally matched byte by byte, so each char outside of
+    /// ASCII takes a chain of two or more transitions. With this, it takes
+    /// just one, which makes matching faster for non-Latin scripts, but each
+    /// transition takes three bytes instead of one. Hyphenation is the same
+    /// either way. Defaults to `false`.
+    pub fn set_chars(&mut self, chars: bool) {
+        self.chars = chars;
+    }
+
+    /// Set the metadata to store in the trie, like the name of the language
+    /// or the source and version of the patterns.
+    ///
+    /// It can be read back with [`Trie::metadata`](crate::Trie::metadata).
+    /// Encoding fails with [`BuildError::MetadataTooLong`] if it is longer
+    /// than [`MAX_METADATA_LEN`] bytes. Defaults to the empty string, which
+    /// isn't stored at all.
+    pub fn set_metadata(&mut self, metadata: &str) {
+        self.metadata = metadata.into();
+    }
+
+    /// Insert all patterns and exceptions of a TeX pattern file into the trie.
+    ///
+    /// The hyphenmin assignments in the file become the trie's
+    /// [bounds](Self::set_bounds). This can be called for multiple files to
+    /// build a multilingual trie, where later files take precedence over
+    /// earlier ones like with [`insert`](Self::insert).
+    ///
+    /// Fails with [`BuildError::LevelOutOfRange`] if a pattern has a level
+    /// that is not a single digit and with
+    /// [`BuildError::PatternDistanceTooLarge`] if its levels are too far
+    /// apart to be encoded. Nothing is inserted in these cases.
+    ///
+    /// Since there is no file to resolve them against, `\input` commands are
+    /// ignored. Use [`insert_tex_file`](Self::insert_tex_file) to follow them.
+    ///
+    /// # Panics
+    /// Panics if the trie was already compressed.
+    pub fn insert_tex(&mut self, tex: &str) -> Result<(), BuildError> {
+        let mut result = Ok(());
+        scan(tex, |entry, offset| {
+            if let (Entry::Pattern(pat), Ok(())) = (entry, &result) {
+                result = check_levels(tex, pat, offset);
+            }
+        });
+        result?;
+
+        scan(tex, |entry, _| match entry {
+            Entry::Pattern(pat) => self.insert(pat),
+            Entry::Exception(word) => self.insert_exception(word),
+            Entry::LeftMin(min) => self.bounds.0 = min,
+            Entry::RightMin(min) => self.bounds.1 = min,
+            Entry::Input(_) => {}
+        });
+        Ok(())
+    }
+
+    /// Read a TeX pattern file from disk and insert all of its patterns and
+    /// exceptions into the trie.
+    ///
+    /// Works like [`insert_tex`](Self::insert_tex), but also inserts the
+    /// contents of files included with `\input path` or `\input{path}` at
+    /// the point of the command. Relative paths are resolved against the
+    /// directory of the including file and, like in TeX, `.tex` is appended
+    /// to a path without extension if there is no file at it. See
+    /// [`read_tex_file`] for how each file is read.
+    ///
+    /// Fails with [`BuildError::InputCycle`] if a file includes itself and
+    /// with [`BuildError::Input`] if an included file cannot be read or has a
+    /// level out of range. Nothing is inserted in these cases.
+    ///
+    /// # Panics
+    /// Panics if the trie was already compressed.
+    pub fn insert_tex_file(&mut self, path: &Path) -> Result<(), BuildError> {
+        // Read and check all files first, so that nothing is inserted if any
+        // of them fails. They end up in the order they are inserted in.
+        let mut files = vec![];
+        load_tex_file(path, &mut vec![], &mut files, true)?;
+        self.insert_loaded(&mut files.iter());
+        Ok(())
+    }
+
+    /// Insert the next of the files loaded by [`load_tex_file`], including
+    /// the files it inputs.
+    fn insert_loaded<'a, I>(&mut self, files: &mut I)
+    where
+        I: Iterator<Item = &'a String>,
+    {
+        let tex = match files.next() {
+            Some(tex) => tex,
+            None => return,
+        };
+
+        scan(tex, |entry, _| match entry {
+            Entry::Pattern(pat) => self.insert(pat),
+            Entry::Exception(word) => self.insert_exception(word),
+            Entry::LeftMin(min) => self.bounds.0 = min,
+            Entry::RightMin(min) => self.bounds.1 = min,
+            Entry::Input(_) => self.insert_loaded(files),
+        });
+    }
+
     /// Insert a pattern like `.a1bc2d` into the trie.
-    fn insert(&mut self, pattern: &str) {
+    ///
+    /// The digits are the levels between the letters around them, where
+    /// omitted digits stand for zero. Explicit zeros are thus dropped, so
+    /// that `a0b` is the same pattern as `ab`. A dot matches the start or end
+    /// of a word. When hyphenating, the maximum level of all matching patterns
+    /// is taken at each position and odd levels permit a break.
+    ///
+    /// The letters are lowercased and typographic apostrophes (U+2019) are
+    /// stored as ASCII ones, just like words are when hyphenating them.
+    ///
+    /// If a pattern with the same letters was inserted before, it is replaced.
+    /// If their levels differ, this is recorded as a [conflict](Self::conflicts).
+    ///
+    /// The levels must be at most [`MAX_LEVEL_DISTANCE`] bytes apart, or
+    /// [`encode`](Self::encode) fails. Unlike here, this is already checked
+    /// when inserting a pattern file.
+    ///
+    /// # Panics
+    /// Panics if the trie was already compressed.
+    pub fn insert(&mut self, pattern: &str) {
+        let mut path = vec![];
+        let mut dist = 0;
+        let mut levels = vec![];
+
+        // Split the pattern into the letters and the levels between them.
+        for c in pattern.chars() {
+            if c == '0' {
+                continue;
+            } else if let Some(level) = c.to_digit(10) {
+                levels.push((dist, level as u8));
+                dist = 0;
+            } else {
+                let mut buf = [0; 4];
+                let bytes = crate::normalize(c).encode_utf8(&mut buf).as_bytes();
+                path.extend_from_slice(bytes);
+                dist += bytes.len();
+            }
+        }
+
+        self.insert_path(&path, &levels, false);
+    }
+
+    /// Insert an exception like `as-so-ciate` into the trie.
+    ///
+    /// The word is then hyphenated exactly at its hyphens, regardless of the
+    /// patterns. Exceptions only match whole words, ignore case and match
+    /// typographic apostrophes like ASCII ones. If an
+    /// exception for the same word was inserted before, it is replaced and a
+    /// [conflict](Self::conflicts) is recorded if the hyphens differ.
+    ///
+    /// # Panics
+    /// Panics if the trie was already compressed.
+    pub fn insert_exception(&mut self, word: &str) {
+        // Exceptions live below the `EXCEPTION` transition of the root, which
+        // stands in for the leading dot, and end with a dot so that they only
+        // ever match the whole word. Each hyphen becomes an odd level.
+        let mut path = vec![crate::EXCEPTION];
+        let mut dist = 1;
+        let mut levels = vec![];
+
+        for c in word.chars() {
+            if c == '-' {
+                levels.push((dist, 1));
+                dist = 0;
+            } else {
+                let mut buf = [0; 4];
+                let bytes = crate::normalize(c).encode_utf8(&mut buf).as_bytes();
+                path.extend_from_slice(bytes);
+                dist += bytes.len();
+            }
+        }
+
+        path.push(b'.');
+        self.insert_path(&path, &levels, true);
+    }
+
+    /// The conflicts between the inserted patterns and exceptions.
+    ///
+    /// Whenever an insertion replaced a pattern or exception with the same
+    /// letters but different levels, both are listed here in insertion order.
+    /// Exact duplicates are not conflicts.
+    pub fn conflicts(&self) -> &[Conflict] {
+        &self.conflicts
+    }
+
+    /// The number of distinct patterns in the trie.
+    ///
+    /// Exact duplicates and patterns replaced by later ones are counted once.
+    pub fn pattern_count(&self) -> usize {
+        self.count_final(false)
+    }
+
+    /// The number of distinct exceptions in the trie.
+    pub fn exception_count(&self) -> usize {
+        self.count_final(true)
+    }
+
+    /// Count the paths from the root to a node with levels, either those
+    /// below the [`EXCEPTION`](crate::EXCEPTION) transition or all others.
+    fn count_final(&self, exceptions: bool) -> usize {
+        let mut count = 0;
+        let mut stack = vec![(self.root, false)];
+        while let Some((state, below)) = stack.pop() {
+            let node = &self.nodes[state];
+            if node.levels.is_some() && below == exceptions {
+                count += 1;
+            }
+            for (&b, &target) in node.trans.iter().zip(&node.targets) {
+                let below = below || (state == self.root && b == crate::EXCEPTION);
+                stack.push((target, below));
+            }
+        }
+        count
+    }
+
+    /// Insert a path of transitions with levels for its final node.
+    fn insert_path(&mut self, path: &[u8], levels: &[(usize, u8)], exception: bool) {
+        assert!(!self.compressed, "cannot insert into a compressed trie");
         let mut state = 0;
-        let mut dist = 0;
-        let mut levels = vec![];
 
         // Follow the existing transitions / add new ones.
-        for b in pattern.bytes() {
-            if b.is_ascii_digit() {
-                levels.push((dist, b - b'0'));
-                dist = 0;
+        for &b in path {
+            let len = self.nodes.len();
+            let node = &mut self.nodes[state];
+            if let Some(i) = node.trans.iter().position(|&x| x == b) {
+                state = node.targets[i];
             } else {
-                let len = self.nodes.len();
-                let node = &mut self.nodes[state];
-                if let Some(i) = node.trans.iter().position(|&x| x == b) {
-                    state = node.targets[i];
-                } else {
-                    node.trans.push(b);
-                    node.targets.push(len);
-                    state = len;
-                    self.nodes.push(Node::default());
-                }
-                dist += 1;
+                node.trans.push(b);
+                node.targets.push(len);
+                state = len;
+                self.nodes.push(Node::default());
+            }
+        }
+
+        // Keep exact duplicates and record differing levels as conflicts.
+        if let Some((offset, len)) = self.nodes[state].levels {
+            let old = &self.levels[offset..offset + len];
+            if old == levels {
+                return;
             }
+
+            let offsets = |levels: &[(usize, u8)]| {
+                let mut offset = 0;
+                levels
+                    .iter()
+                    .map(|&(dist, level)| {
+                        offset += dist;
+                        (offset, level)
+                    })
+                    .collect::<Vec<_>>()
+            };
+            self.conflicts.push(Conflict {
+                old: crate::decompile(path, offsets(old).into_iter(), exception),
+                new: crate::decompile(path, offsets(levels).into_iter(), exception),
+            });
         }
 
         // Try to reuse existing levels.
         let mut offset = 0;
-        while offset < self.levels.len() && !self.levels[offset..].starts_with(&levels) {
+        while offset < self.levels.len() && !self.levels[offset..].starts_with(levels) {
             offset += 1;
         }
 
         // If there was no matching level "substring", we must append the new
         // levels at the end.
         if offset == self.levels.len() {
-            self.levels.extend(&levels);
+            self.levels.extend(levels);
         }
 
         // Add levels for the final node.
         self.nodes[state].levels = Some((offset, levels.len()));
     }
 
+    /// The number of nodes in the trie, including the root.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
     /// Perform suffix compression on the trie.
-    fn compress(&mut self) {
+    ///
+    /// This merges identical subtrees and makes the encoded trie much
+    /// smaller. Afterwards, no more patterns can be inserted.
+    ///
+    /// A trie already shares all common prefixes, so merging its identical
+    /// subtrees bottom-up yields the automaton with the fewest nodes that
+    /// accepts the same patterns, as long as nodes with the same transitions
+    /// in a different order count as different. See
+    /// [`compress2`](Self::compress2) to also merge those.
+    ///
+    /// Returns the number of nodes before and after.
+    pub fn compress(&mut self) -> Compression {
+        let before = self.nodes.len();
         let mut map = HashMap::new();
         let mut new = vec![];
-        self.root = self.compress_node(0, &mut map, &mut new);
+        self.root = self.compress_node(self.root, &mut map, &mut new);
         self.nodes = new;
+        self.compressed = true;
+        Compression { before, after: self.nodes.len() }
+    }
+
+    /// Perform suffix compression on the trie after sorting the transitions
+    /// of each node by their bytes.
+    ///
+    /// Transitions are otherwise kept in the order in which they were
+    /// inserted, so two nodes with the same transitions and levels may still
+    /// differ in [`compress`](Self::compress) if the patterns leading to them
+    /// were inserted in a different order. Sorting first lets them be merged
+    /// as well, which results in the minimal automaton. This doesn't change
+    /// how words are hyphenated, but the encoded trie is not byte-identical to
+    /// that of `compress`.
+    ///
+    /// Returns the number of nodes before and after.
+    pub fn compress2(&mut self) -> Compression {
+        for node in &mut self.nodes {
+            let mut pairs: Vec<_> =
+                node.trans.iter().copied().zip(node.targets.iter().copied()).collect();
+            pairs.sort_unstable();
+            (node.trans, node.targets) = pairs.into_iter().unzip();
+        }
+        self.compress()
     }
 
     /// Recursively compress a node.
@@ -159,8 +1472,41 @@ impl TrieBuilder {
         })
     }
 
-    /// Encode the tree.
-    fn encode(&self) -> Vec<u8> {
+    /// Encode the trie into the binary format understood by
+    /// [`Lang::from_bytes`](crate::Lang).
+    ///
+    /// Compressing the trie first is optional. Without it, the encoded trie is
+    /// larger, but its nodes are laid out in the order in which they were
+    /// inserted, starting with the root, which makes the output of the encoder
+    /// easier to debug. Both hyphenate the same.
+    ///
+    /// The encoded bytes only depend on the inserted patterns and exceptions,
+    /// their order and the settings of the builder, so builds are
+    /// reproducible. Hash maps are only used for lookups, never iterated.
+    pub fn encode(&self) -> Result<Vec<u8>, BuildError> {
+        let mut data = vec![];
+        self.encode_to(&mut data)?;
+        Ok(data)
+    }
+
+    /// Encode the trie like [`encode`](Self::encode), but write it to `w`
+    /// instead of returning it.
+    ///
+    /// The header holds the address of the root node and nodes refer to each
+    /// other by relative addresses, which go backwards as well as forwards.
+    /// Thus, the addresses of all nodes are computed in a first pass, which
+    /// also checks that the trie can be encoded. Only then, the trie is
+    /// written out in order in a second pass with just one node buffered at a
+    /// time. Nothing is written if the trie can't be encoded, but the output
+    /// may be incomplete if writing fails.
+    pub fn encode_to<W: Write>(&self, w: &mut W) -> Result<(), BuildError> {
+        let (nodes, root, width) = if self.chars {
+            let (nodes, root) = self.char_nodes();
+            (Cow::Owned(nodes), root, 3)
+        } else {
+            (Cow::Borrowed(self.nodes.as_slice()), self.root, 1)
+        };
+
         let start = 4 + self.levels.len();
 
         // Compute an address estimate for each node. We can't know the final
@@ -169,12 +1515,12 @@ impl TrieBuilder {
         // addresses.
         let mut addr = start;
         let mut estimates = vec![];
-        for node in &self.nodes {
+        for node in nodes.iter() {
             estimates.push(addr);
             addr += 1
-                + ((node.trans.len() >= 31) as usize)
+                + ((node.targets.len() >= 31) as usize)
                 + 2 * (node.levels.is_some() as usize)
-                + (1 + 3) * node.trans.len();
+                + (width + 4) * node.targets.len();
         }
 
         // Use the address estimates to determine how many bytes to use for each
@@ -182,85 +1528,300 @@ impl TrieBuilder {
         let mut addr = start;
         let mut addrs = vec![];
         let mut strides = vec![];
-        for (i, node) in self.nodes.iter().enumerate() {
-            let stride = node
-                .targets
-                .iter()
-                .map(|&t| how_many_bytes(estimates[t] as isize - estimates[i] as isize))
-                .max()
-                .unwrap_or(1);
+        for (i, node) in nodes.iter().enumerate() {
+            let mut stride = 1;
+            for &t in &node.targets {
+                let delta = estimates[t] as isize - estimates[i] as isize;
+                let bytes = how_many_bytes(delta)
+                    .ok_or(BuildError::AddressOutOfRange { node: i, value: delta })?;
+                stride = stride.max(bytes);
+            }
+
+            if node.targets.len() > MAX_TRANSITIONS {
+                let count = node.targets.len();
+                return Err(BuildError::TooManyTransitions { node: i, count });
+            }
+
+            if let Some((offset, len)) = node.levels {
+                let offset = 4 + offset;
+                if offset > MAX_LEVEL_OFFSET {
+                    return Err(BuildError::LevelOffsetTooLarge { node: i, offset });
+                }
+                if len > MAX_LEVELS_PER_NODE {
+                    return Err(BuildError::TooManyLevels { node: i, count: len });
+                }
+            }
 
             addrs.push(addr);
             strides.push(stride);
             addr += 1
-                + ((node.trans.len() >= 31) as usize)
+                + ((node.targets.len() >= 31) as usize)
                 + 2 * (node.levels.is_some() as usize)
-                + (1 + stride) * node.trans.len();
+                + (width + stride) * node.targets.len();
         }
 
-        let mut data = vec![];
+        // Check the rest of the header and the levels.
+        if self.metadata.len() > MAX_METADATA_LEN {
+            return Err(BuildError::MetadataTooLong(self.metadata.len()));
+        }
+
+        let mut bounds = [0; 2];
+        for (slot, bound) in bounds.iter_mut().zip([self.bounds.0, self.bounds.1]) {
+            *slot = u8::try_from(bound).map_err(|_| BuildError::BoundTooLarge(bound))?;
+        }
+
+        let root_addr = addrs[root];
+        let root_addr = u32::try_from(root_addr).map_err(|_| {
+            BuildError::AddressOutOfRange { node: root, value: root_addr as isize }
+        })?;
+
+        for (index, &(dist, level)) in self.levels.iter().enumerate() {
+            if dist > MAX_LEVEL_DISTANCE {
+                return Err(BuildError::LevelDistanceTooLarge { index, dist });
+            }
+            debug_assert!(level <= MAX_LEVEL, "too high level");
+        }
+
+        // Encode the fixed-size header. Addresses are relative to the end of
+        // the whole header, including the metadata.
+        let mut flags = 0;
+        if self.checksum {
+            flags |= crate::FLAG_CHECKSUM;
+        }
+        if self.chars {
+            flags |= crate::FLAG_CHARS;
+        }
+        if !self.metadata.is_empty() {
+            flags |= crate::FLAG_METADATA;
+        }
+        let mut buf = vec![];
+        buf.extend(crate::MAGIC);
+        if flags & crate::LAYOUT_FLAGS != 0 {
+            buf.push(crate::VERSION);
+        } else {
+            buf.push(crate::MIN_VERSION);
+        }
+        buf.push(flags);
+        buf.push(0);
+        buf.extend(bounds);
+        w.write_all(&buf)?;
+
+        // Everything after the fixed-size header is covered by the checksum.
+        let mut crc = !0;
+        let mut emit = |buf: &mut Vec<u8>| {
+            crc = crate::crc32_update(crc, buf);
+            let result = w.write_all(buf);
+            buf.clear();
+            result
+        };
+
+        // Encode the metadata, which completes the header.
+        buf.clear();
+        if !self.metadata.is_empty() {
+            buf.extend((self.metadata.len() as u16).to_be_bytes());
+            buf.extend(self.metadata.as_bytes());
+            emit(&mut buf)?;
+        }
 
         // Encode the root address.
-        data.extend(u32::try_from(addrs[self.root]).unwrap().to_be_bytes());
+        buf.extend(root_addr.to_be_bytes());
+        emit(&mut buf)?;
 
         // Encode the levels.
-        for &(dist, level) in &self.levels {
-            assert!(dist <= 24, "too high level distance");
-            assert!(level < 10, "too high level");
-            data.push(dist as u8 * 10 + level);
-        }
+        buf.extend(self.levels.iter().map(|&(dist, level)| dist as u8 * 10 + level));
+        emit(&mut buf)?;
 
         // Encode the nodes.
-        for ((node, &addr), stride) in self.nodes.iter().zip(&addrs).zip(strides) {
-            data.push(
+        let iter = nodes.iter().zip(&addrs).zip(strides);
+        for ((node, &addr), stride) in iter {
+            buf.push(
                 (node.levels.is_some() as u8) << 7
-                    | (stride as u8) << 5
-                    | (node.trans.len().min(31) as u8),
+                    | (stride as u8 - 1) << 5
+                    | (node.targets.len().min(31) as u8),
             );
 
-            if node.trans.len() >= 31 {
-                data.push(u8::try_from(node.trans.len()).expect("too many transitions"));
+            if node.targets.len() >= 31 {
+                buf.push(node.targets.len() as u8);
             }
 
             if let Some((offset, len)) = node.levels {
                 let offset = 4 + offset;
-                assert!(offset < 4096, "too high level offset");
-                assert!(len < 16, "too high level count");
-
                 let offset_hi = (offset >> 4) as u8;
                 let offset_lo = ((offset & 15) << 4) as u8;
                 let len = len as u8;
 
-                data.push(offset_hi);
-                data.push(offset_lo | len);
+                buf.push(offset_hi);
+                buf.push(offset_lo | len);
             }
 
-            data.extend(&node.trans);
+            buf.extend(&node.trans);
 
             for &target in &node.targets {
                 let delta = addrs[target] as isize - addr as isize;
-                to_be_bytes(&mut data, delta, stride);
+                to_be_bytes(&mut buf, delta, stride);
+            }
+
+            emit(&mut buf)?;
+        }
+
+        // Encode the checksum of everything after the header.
+        if self.checksum {
+            w.write_all(&(!crc).to_be_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Check that an encoded trie holds exactly the patterns and exceptions of
+    /// this builder, as a safety net against encoder bugs and corruption.
+    ///
+    /// Pass as `trie` the output of [`encode`](Self::encode). It is decoded
+    /// and walked completely, so this takes about as long as encoding. Fails
+    /// with [`BuildError::Trie`] if the trie can't be decoded or its checksum
+    /// doesn't match and with [`BuildError::Mismatch`] if a pattern or
+    /// exception is missing, has different levels or shouldn't be there.
+    pub fn verify(&self, trie: &[u8]) -> Result<(), BuildError> {
+        check_header(trie)?;
+        if self.checksum {
+            crate::check_checksum(trie)?;
+        }
+
+        let root = State::root(trie).ok_or(TrieError::Malformed)?;
+        let mut decoded = HashMap::new();
+        walk(root, &mut vec![], &mut |path, state| {
+            decoded.insert(path.to_vec(), state.levels().collect::<Vec<_>>());
+        })?;
+
+        let mismatch = |path: &[u8], levels: Vec<(usize, u8)>| {
+            let exception = path.first() == Some(&crate::EXCEPTION);
+            BuildError::Mismatch(crate::decompile(path, levels.into_iter(), exception))
+        };
+
+        self.visit(|path, levels| match decoded.remove(path) {
+            Some(found) if found == levels => Ok(()),
+            _ => Err(mismatch(path, levels)),
+        })?;
+
+        match decoded.into_iter().min() {
+            Some((path, levels)) => Err(mismatch(&path, levels)),
+            None => Ok(()),
+        }
+    }
+
+    /// Call `f` with the path to each node with levels and the levels as
+    /// offsets into the path, stopping at the first error.
+    fn visit<F>(&self, mut f: F) -> Result<(), BuildError>
+    where
+        F: FnMut(&[u8], Vec<(usize, u8)>) -> Result<(), BuildError>,
+    {
+        let mut stack = vec![(self.root, vec![])];
+        while let Some((state, path)) = stack.pop() {
+            let node = &self.nodes[state];
+            if let Some((offset, len)) = node.levels {
+                let mut end = 0;
+                let levels = self.levels[offset..offset + len]
+                    .iter()
+                    .map(|&(dist, level)| {
+                        end += dist;
+                        (end, level)
+                    })
+                    .collect();
+                f(&path, levels)?;
             }
+            for (&b, &target) in node.trans.iter().zip(&node.targets) {
+                let mut path = path.clone();
+                path.push(b);
+                stack.push((target, path));
+            }
+        }
+        Ok(())
+    }
+
+    /// Convert the trie into one whose transitions are labelled with whole
+    /// chars, stored as three bytes each, and return it with its root.
+    ///
+    /// The nodes within the UTF-8 encoding of a char are skipped and nodes
+    /// shared through suffix compression stay shared. Like in a compressed
+    /// trie, the root comes last.
+    fn char_nodes(&self) -> (Vec<Node>, usize) {
+        let mut map = HashMap::new();
+        let mut nodes = vec![];
+        let root = self.char_node(self.root, &mut map, &mut nodes);
+        (nodes, root)
+    }
+
+    /// Recursively convert a node at a char boundary.
+    fn char_node(
+        &self,
+        node: usize,
+        map: &mut HashMap<usize, usize>,
+        nodes: &mut Vec<Node>,
+    ) -> usize {
+        if let Some(&idx) = map.get(&node) {
+            return idx;
         }
 
-        data
+        let mut chars = vec![];
+        self.chars_below(node, node == self.root, &mut vec![], &mut chars);
+
+        let mut x = Node { levels: self.nodes[node].levels, ..Node::default() };
+        for (label, target) in chars {
+            x.trans.extend_from_slice(&label.to_be_bytes()[1..]);
+            x.targets.push(self.char_node(target, map, nodes));
+        }
+
+        let idx = nodes.len();
+        nodes.push(x);
+        map.insert(node, idx);
+        idx
+    }
+
+    /// Collect the chars spelled out by the transitions below a node at a char
+    /// boundary, together with the nodes they lead to.
+    fn chars_below(
+        &self,
+        node: usize,
+        root: bool,
+        bytes: &mut Vec<u8>,
+        chars: &mut Vec<(u32, usize)>,
+    ) {
+        let node = &self.nodes[node];
+        for (&b, &target) in node.trans.iter().zip(&node.targets) {
+            if root && b == crate::EXCEPTION {
+                chars.push((crate::EXCEPTION_CHAR, target));
+                continue;
+            }
+
+            bytes.push(b);
+            if crate::utf8_len(bytes[0]) == Some(bytes.len()) {
+                if let Some(c) = str::from_utf8(bytes).ok().and_then(|s| s.chars().next())
+                {
+                    chars.push((c.into(), target));
+                }
+            } else if bytes.len() < 4 {
+                self.chars_below(target, false, bytes, chars);
+            }
+            bytes.pop();
+        }
     }
 }
 
 /// How many bytes are needed to encode a signed number.
-fn how_many_bytes(num: isize) -> usize {
+fn how_many_bytes(num: isize) -> Option<usize> {
     if i8::try_from(num).is_ok() {
-        1
+        Some(1)
     } else if i16::try_from(num).is_ok() {
-        2
+        Some(2)
     } else if (-(1 << 23)..(1 << 23)).contains(&num) {
-        3
+        Some(3)
+    } else if i32::try_from(num).is_ok() {
+        Some(4)
     } else {
-        panic!("too large number");
+        None
     }
 }
 
-/// Encode a signed number with 1, 2 or 3 bytes.
+/// Encode a signed number with 1, 2, 3 or 4 bytes.
 fn to_be_bytes(buf: &mut Vec<u8>, num: isize, stride: usize) {
     if stride == 1 {
         buf.extend(i8::try_from(num).unwrap().to_be_bytes());
@@ -271,7 +1832,932 @@ fn to_be_bytes(buf: &mut Vec<u8>, num: isize, stride: usize) {
         buf.push((unsigned >> 16) as u8);
         buf.push((unsigned >> 8) as u8);
         buf.push(unsigned as u8);
+    } else if stride == 4 {
+        buf.extend(i32::try_from(num).unwrap().to_be_bytes());
     } else {
         panic!("invalid stride");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_and_measure, build_trie, build_trie_from_file, build_trie_with, dump_trie,
+        merge_tries, parse, parse_exceptions, parse_reader, read_tex_file,
+        read_tex_files, trie_stats, BuildError, Compression, Conflict, Node, TrieBuilder,
+        TrieStats, MAX_LEVEL, MAX_LEVELS_PER_NODE, MAX_LEVEL_DISTANCE, MAX_LEVEL_OFFSET,
+        MAX_METADATA_LEN, MAX_TRANSITIONS,
+    };
+    use crate::{
+        check_checksum, check_header, crc32, hyphenate_from, is_letter, stored_bounds,
+        trie_version, State, Trie, TrieError, HEADER_LEN, MIN_VERSION, VERSION,
+    };
+
+    const TEX: &str = r"
+        \patterns{ % Some patterns.
+            1b 1c 1d
+        }
+        \hyphenation{ % Some exceptions.
+            ab-cd % A comment.
+            Bc-Da
+            abc
+        }
+    ";
+
+    fn hyphenate(trie: &[u8], word: &str) -> String {
+        hyphenate_from(word, State::root(trie).unwrap(), 1, 1, is_letter).join("-")
+    }
+
+    #[test]
+    fn test_parse_exceptions() {
+        let mut patterns = vec![];
+        let mut exceptions = vec![];
+        parse(TEX, |pat| patterns.push(pat.to_string()));
+        parse_exceptions(TEX, |word| exceptions.push(word.to_string()));
+        assert_eq!(patterns, ["1b", "1c", "1d"]);
+        assert_eq!(exceptions, ["ab-cd", "Bc-Da", "abc"]);
+    }
+
+    #[test]
+    fn test_parse_reader() {
+        let tex = "% Ignore \\patterns{ x1y }\n\\lefthyphenmin=1 \\\\patterns{\n\
+                   ä1b 2ßc% ö1ö\n\t.ab3c4\u{3000}1€ }\\patterns{}\\patterns{z1";
+        let expected = ["ä1b", "2ßc", ".ab3c4", "1€", "z1"];
+
+        let mut patterns = vec![];
+        parse(tex, |pat| patterns.push(pat.to_string()));
+        assert_eq!(patterns, expected);
+
+        // The chunks split commands, comments and chars.
+        for capacity in [1, 3] {
+            let reader = std::io::BufReader::with_capacity(capacity, tex.as_bytes());
+            let mut patterns = vec![];
+            parse_reader(reader, |pat| patterns.push(pat.to_string())).unwrap();
+            assert_eq!(patterns, expected);
+        }
+
+        let bytes = b"\\patterns{ a1b c\xC3";
+        let reader = std::io::BufReader::with_capacity(1, &bytes[..]);
+        let mut patterns = vec![];
+        let err = parse_reader(reader, |pat| patterns.push(pat.to_string())).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(patterns, ["a1b"]);
+    }
+
+    #[test]
+    fn test_tight_braces() {
+        // A closing brace ends the last word even without whitespace before it.
+        let spaced = build_trie(r"\patterns{ a1b } \hyphenation{ ab-c }").unwrap();
+        let tight = build_trie(r"\patterns{a1b}\hyphenation{ab-c}").unwrap();
+        assert_eq!(tight, spaced);
+
+        let mut patterns = vec![];
+        parse(r"\patterns{a1b b2c1}\patterns{1c}", |pat| patterns.push(pat.to_string()));
+        assert_eq!(patterns, ["a1b", "b2c1", "1c"]);
+    }
+
+    #[test]
+    fn test_block_spacing() {
+        // TeX skips whitespace between a command and its argument.
+        for tex in [
+            r"\patterns{ a1b } \hyphenation{ ab-c }",
+            r"\patterns { a1b } \hyphenation { ab-c }",
+            "\\patterns\n{ a1b }\n\\hyphenation\t\r\n {ab-c}",
+        ] {
+            let mut patterns = vec![];
+            parse(tex, |pat| patterns.push(pat.to_string()));
+            assert_eq!(patterns, ["a1b"]);
+            let mut exceptions = vec![];
+            parse_exceptions(tex, |word| exceptions.push(word.to_string()));
+            assert_eq!(exceptions, ["ab-c"]);
+        }
+
+        // Longer commands and other args don't open a block.
+        let mut patterns = vec![];
+        let tex = r"\patternsx{ a1b } \patterns \relax{ b1c } \patterns % { c1d }";
+        parse(tex, |pat| patterns.push(pat.to_string()));
+        assert!(patterns.is_empty());
+    }
+
+    #[test]
+    fn test_block_comments() {
+        // Comments end the word before them and run to the end of the line.
+        let tex = "\\patterns{ a1b % note } 9x9\n c2d e3f%x\r\n%\n\tg4h% }\n}\n\
+                   \\hyphenation{ab-c%d-e\nf-g % h-i\n}";
+        let mut patterns = vec![];
+        let mut exceptions = vec![];
+        parse(tex, |pat| patterns.push(pat.to_string()));
+        parse_exceptions(tex, |word| exceptions.push(word.to_string()));
+        assert_eq!(patterns, ["a1b", "c2d", "e3f", "g4h"]);
+        assert_eq!(exceptions, ["ab-c", "f-g"]);
+
+        let reader = std::io::BufReader::with_capacity(1, tex.as_bytes());
+        let mut chunked = vec![];
+        parse_reader(reader, |pat| chunked.push(pat.to_string())).unwrap();
+        assert_eq!(chunked, patterns);
+    }
+
+    #[test]
+    fn test_bom() {
+        let path = std::path::Path::new("tests/fixtures/bom.tex");
+        let tex = read_tex_file(path).unwrap();
+        assert!(tex.starts_with('\u{FEFF}'));
+        let expected = build_trie_from_file("tests/fixtures/dump.tex".as_ref()).unwrap();
+        assert_eq!(build_trie_from_file(path).unwrap(), expected);
+        assert_eq!(build_trie(&tex).unwrap(), expected);
+
+        let mut patterns = vec![];
+        let reader =
+            std::io::BufReader::with_capacity(1, "\u{FEFF}\\patterns{ a1b }".as_bytes());
+        parse_reader(reader, |pat| patterns.push(pat.to_string())).unwrap();
+        assert_eq!(patterns, ["a1b"]);
+
+        // The byte order mark doesn't count as a column.
+        for tex in ["\\patterns{ a10b }", "\u{FEFF}\\patterns{ a10b }"] {
+            let err = build_trie(tex).unwrap_err();
+            assert_eq!(err.to_string(), "line 1, column 13: level 10 out of range");
+        }
+    }
+
+    #[test]
+    fn test_endinput() {
+        let tex = "\\patterns{ a1b }\\endinputs \\endinput\n\\patterns{x1y}\n";
+        let mut patterns = vec![];
+        parse(tex, |pat| patterns.push(pat.to_string()));
+        assert_eq!(patterns, ["a1b"]);
+
+        let trie = build_trie(tex).unwrap();
+        assert_eq!(hyphenate(&trie, "xyab"), "xya-b");
+
+        // Nothing after `\endinput` is read, not even invalid UTF-8.
+        let bytes = b"\\patterns{ a1b } \\endinput \xFF\\patterns{x1y}";
+        let reader = std::io::BufReader::with_capacity(3, &bytes[..]);
+        let mut patterns = vec![];
+        parse_reader(reader, |pat| patterns.push(pat.to_string())).unwrap();
+        assert_eq!(patterns, ["a1b"]);
+    }
+
+    #[test]
+    fn test_preamble() {
+        let tex = read_tex_file("tests/fixtures/preamble.tex".as_ref()).unwrap();
+        let mut patterns = vec![];
+        parse(&tex, |pat| patterns.push(pat.to_string()));
+        assert_eq!(patterns, ["a1b", "1c"]);
+
+        let trie = build_trie(&tex).unwrap();
+        assert_eq!(stored_bounds(&trie), (1, 2));
+        assert_eq!(hyphenate(&trie, "abc"), "a-b-c");
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let mut builder = TrieBuilder::new();
+        builder
+            .insert_tex(
+                r"\patterns{ a1b .c2d a1b bc 3ä1a c2d4 .c2d b1c }
+                  \hyphenation{ ab-cd AB-CD abc-d }",
+            )
+            .unwrap();
+        let conflict = Conflict { old: "bc".into(), new: "b1c".into() };
+        assert_eq!(builder.conflicts()[0], conflict);
+        assert_eq!(
+            builder.conflicts()[1].to_string(),
+            "abc-d replaces ab-cd with different levels",
+        );
+        assert_eq!(builder.conflicts().len(), 2);
+        assert_eq!(builder.pattern_count(), 5);
+        assert_eq!(builder.exception_count(), 1);
+
+        builder.compress();
+        assert_eq!(builder.pattern_count(), 5);
+        let trie = builder.encode().unwrap();
+        assert_eq!(hyphenate(&trie, "abc"), "a-b-c");
+        assert_eq!(hyphenate(&trie, "ABCD"), "ABC-D");
+    }
+
+    #[test]
+    fn test_zero_levels() {
+        let explicit = build_trie(r"\patterns{ a0b1c 0x1y0 0d0 }").unwrap();
+        let implicit = build_trie(r"\patterns{ ab1c x1y d }").unwrap();
+        assert_eq!(explicit, implicit);
+        for word in ["abc", "xyx", "dabcd"] {
+            assert_eq!(hyphenate(&explicit, word), hyphenate(&implicit, word));
+        }
+        assert_eq!(hyphenate(&explicit, "xyabc"), "x-yab-c");
+
+        // The zeros are the same as omitted levels, so they don't conflict.
+        let mut builder = TrieBuilder::new();
+        builder.insert("ab1c");
+        builder.insert("a0b1c0");
+        assert!(builder.conflicts().is_empty());
+    }
+
+    #[test]
+    fn test_apostrophe() {
+        // Typographic apostrophes are stored as ASCII ones, which is what
+        // they are matched as.
+        let trie = build_trie(r"\patterns{ n1’t } \hyphenation{ ab-c’d }").unwrap();
+        assert_eq!(hyphenate(&trie, "don’t"), "don-’t");
+        assert_eq!(hyphenate(&trie, "don't"), "don-'t");
+        assert_eq!(hyphenate(&trie, "abc’d"), "ab-c’d");
+        assert_eq!(hyphenate(&trie, "ABC'D"), "AB-C'D");
+        assert_eq!(
+            trie,
+            build_trie(r"\patterns{ n1't } \hyphenation{ ab-c'd }").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_exceptions() {
+        let trie = build_trie(TEX).unwrap();
+        assert_eq!(hyphenate(&trie, "abcd"), "ab-cd");
+        assert_eq!(hyphenate(&trie, "BCDA"), "BC-DA");
+        assert_eq!(hyphenate(&trie, "abc"), "abc");
+        assert_eq!(hyphenate(&trie, "dabc"), "da-b-c");
+        assert_eq!(hyphenate(&trie, "abcda"), "a-b-c-da");
+
+        // An exception doesn't match within a longer word.
+        let trie = build_trie(r"\patterns{ 1b 1c } \hyphenation{ abc }").unwrap();
+        assert_eq!(hyphenate(&trie, "abc"), "abc");
+        assert_eq!(hyphenate(&trie, "xabc"), "xa-b-c");
+        assert_eq!(hyphenate(&trie, "abcx"), "a-b-cx");
+    }
+
+    #[test]
+    fn test_boundaries() {
+        // The dots only match at the start and end of a word, where they
+        // suppress the breaks that `1n` and `1t` allow elsewhere.
+        let trie = build_trie(r"\patterns{ 1n .u2n 1t 2t. }").unwrap();
+        assert_eq!(hyphenate(&trie, "unto"), "un-to");
+        assert_eq!(hyphenate(&trie, "UNTO"), "UN-TO");
+        assert_eq!(hyphenate(&trie, "bunto"), "bu-n-to");
+        assert_eq!(hyphenate(&trie, "untunt"), "un-tu-nt");
+        assert_eq!(hyphenate(&trie, "unt"), "unt");
+
+        // Each run between separators has its own boundaries.
+        assert_eq!(hyphenate(&trie, "bunt-unto"), "bu-nt-un-to");
+
+        // A dot is a transition like any other.
+        let mut builder = TrieBuilder::new();
+        builder.insert(".u2n");
+        let root = &builder.nodes[0];
+        assert_eq!(root.trans, [b'.']);
+        assert_eq!(builder.nodes[root.targets[0]].trans, [b'u']);
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let tex = format!("\\patterns{{ {}1 }}", "a".repeat(25));
+        let err = build_trie(&tex).unwrap_err();
+        assert!(matches!(err, BuildError::PatternDistanceTooLarge { dist: 25, .. }));
+
+        let mut builder = TrieBuilder::new();
+        builder.insert(&format!("{}1", "a".repeat(25)));
+        let err = builder.encode().unwrap_err();
+        assert!(matches!(err, BuildError::LevelDistanceTooLarge { dist: 25, .. }));
+
+        let tex = format!("\\patterns{{ {} }}", "1a".repeat(16));
+        let err = build_trie(&tex).unwrap_err();
+        assert!(matches!(err, BuildError::TooManyLevels { count: 16, .. }));
+    }
+
+    #[test]
+    fn test_limits() {
+        let distance = |dist| format!("\\patterns{{ {}1 }}", "a".repeat(dist));
+        assert!(build_trie(&distance(MAX_LEVEL_DISTANCE)).is_ok());
+        let err = build_trie(&distance(MAX_LEVEL_DISTANCE + 1)).unwrap_err();
+        assert!(matches!(err, BuildError::PatternDistanceTooLarge { .. }));
+
+        let levels = |count| format!("\\patterns{{ {} }}", "1a".repeat(count));
+        assert!(build_trie(&levels(MAX_LEVELS_PER_NODE)).is_ok());
+        let err = build_trie(&levels(MAX_LEVELS_PER_NODE + 1)).unwrap_err();
+        assert!(matches!(err, BuildError::TooManyLevels { .. }));
+
+        let level = |level| format!("\\patterns{{ a{level}b }}");
+        assert!(build_trie(&level(MAX_LEVEL)).is_ok());
+        let err = build_trie(&level(MAX_LEVEL + 1)).unwrap_err();
+        assert!(matches!(err, BuildError::LevelOutOfRange { .. }));
+
+        let transitions = |count: usize| {
+            let mut builder = TrieBuilder::new();
+            for b in 0..count {
+                builder.insert_path(&[b as u8], &[(1, 1)], false);
+            }
+            builder.compress();
+            builder.encode()
+        };
+        assert!(transitions(MAX_TRANSITIONS).is_ok());
+        let err = transitions(MAX_TRANSITIONS + 1).unwrap_err();
+        assert!(matches!(err, BuildError::TooManyTransitions { .. }));
+
+        // The offsets are relative to the end of the header and the levels
+        // start after the root address.
+        let offset = |offset: usize| {
+            let mut builder = TrieBuilder::new();
+            builder.levels = vec![(1, 1); offset - 4 + 1];
+            builder.nodes[0].levels = Some((offset - 4, 1));
+            builder.encode()
+        };
+        assert!(offset(MAX_LEVEL_OFFSET).is_ok());
+        let err = offset(MAX_LEVEL_OFFSET + 1).unwrap_err();
+        assert!(matches!(err, BuildError::LevelOffsetTooLarge { .. }));
+        assert!(err.to_string().ends_with("(max 4095)"));
+    }
+
+    #[test]
+    fn test_chars() {
+        let tex = read_tex_file("patterns/hyph-ru.tex".as_ref()).unwrap();
+        let mut tries = vec![];
+        for chars in [false, true] {
+            let mut builder = TrieBuilder::new();
+            builder.insert_tex(&tex).unwrap();
+            builder.insert_tex(r"\hyphenation{ ёл-ка }").unwrap();
+            builder.set_chars(chars);
+            builder.compress();
+            tries.push(builder.encode().unwrap());
+        }
+
+        // Chars take fewer but larger transitions.
+        let [bytes, chars] = [&tries[0], &tries[1]].map(|trie| trie_stats(trie).unwrap());
+        assert!(chars.nodes < bytes.nodes);
+        assert!(chars.transitions < bytes.transitions);
+        assert_eq!(chars.levels, bytes.levels);
+
+        for word in ["достопримечательность", "вычислительный", "ёлка", "Ёлка", "ab"]
+        {
+            assert_eq!(hyphenate(&tries[0], word), hyphenate(&tries[1], word));
+        }
+        assert_eq!(hyphenate(&tries[1], "ёлка"), "ёл-ка");
+        assert_eq!(dump_trie(&tries[0]).unwrap(), dump_trie(&tries[1]).unwrap());
+    }
+
+    #[test]
+    fn test_verify() {
+        let tex = read_tex_file("patterns/hyph-de-1996.tex".as_ref()).unwrap();
+        for chars in [false, true] {
+            let mut builder = TrieBuilder::new();
+            builder.insert_tex(&tex).unwrap();
+            builder.insert_tex(TEX).unwrap();
+            builder.set_chars(chars);
+            builder.verify(&builder.encode().unwrap()).unwrap();
+            builder.compress();
+            builder.verify(&builder.encode().unwrap()).unwrap();
+        }
+
+        // Simulate corrupted encodings.
+        let mut builder = TrieBuilder::new();
+        builder.insert_tex(TEX).unwrap();
+        builder.compress();
+        let trie = builder.encode().unwrap();
+        let mut other = TrieBuilder::new();
+        other
+            .insert_tex(r"\patterns{ 1b 1c 2d } \hyphenation{ ab-cd Bc-Da abc }")
+            .unwrap();
+        let err = other.verify(&trie).unwrap_err();
+        assert_eq!(err.to_string(), "2d does not round-trip through the encoded trie");
+
+        // Patterns that shouldn't be there are found as well.
+        let mut extra = TrieBuilder::new();
+        extra.insert_tex(TEX).unwrap();
+        extra.insert("e1");
+        let err = builder.verify(&extra.encode().unwrap()).unwrap_err();
+        assert_eq!(err.to_string(), "e1 does not round-trip through the encoded trie");
+
+        let mut corrupted = trie.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert!(builder.verify(&corrupted).is_err());
+        assert!(matches!(
+            builder.verify(b"HYPH"),
+            Err(BuildError::Trie(TrieError::NotATrie))
+        ));
+
+        builder.set_checksum(true);
+        let mut corrupted = builder.encode().unwrap();
+        corrupted[HEADER_LEN + 6] ^= 0x10;
+        let err = builder.verify(&corrupted).unwrap_err();
+        assert!(matches!(err, BuildError::Trie(TrieError::ChecksumMismatch)));
+    }
+
+    #[test]
+    fn test_encode_to() {
+        let tex = read_tex_file("patterns/hyph-en-us.tex".as_ref()).unwrap();
+        for (tex, checksum) in [(TEX, false), (TEX, true), (tex.as_str(), true)] {
+            let mut builder = TrieBuilder::new();
+            builder.insert_tex(tex).unwrap();
+            builder.set_checksum(checksum);
+            builder.compress();
+            let mut out = vec![];
+            builder.encode_to(&mut out).unwrap();
+            assert_eq!(out, builder.encode().unwrap());
+            assert_eq!(check_checksum(&out).is_ok(), checksum);
+        }
+
+        // Nothing is written for a trie that can't be encoded.
+        let mut builder = TrieBuilder::new();
+        builder.insert(&"1a".repeat(16));
+        let mut out = vec![];
+        assert!(builder.encode_to(&mut out).is_err());
+        assert!(out.is_empty());
+
+        // Writing can fail halfway through.
+        let mut short = [0; HEADER_LEN + 2];
+        let err = TrieBuilder::new().encode_to(&mut short.as_mut_slice()).unwrap_err();
+        assert!(matches!(err, BuildError::Io(_)));
+    }
+
+    #[test]
+    fn test_four_byte_stride() {
+        // The root jumps over more than 8 MiB of filler nodes to reach the
+        // leaf, which doesn't fit into a 3-byte delta.
+        let filler = 1 << 14;
+        let leaf = filler + 1;
+        let mut builder = TrieBuilder::new();
+        builder.nodes[0] = Node {
+            trans: vec![b'a'],
+            targets: vec![leaf],
+            levels: None,
+        };
+        for i in 1..=filler {
+            let trans = (0..255).collect();
+            let targets = vec![i; 255];
+            builder.nodes.push(Node { trans, targets, levels: None });
+        }
+        builder.nodes.push(Node { levels: Some((0, 1)), ..Node::default() });
+        builder.levels.push((1, 1));
+
+        let trie = builder.encode().unwrap();
+        assert!(trie.len() > 1 << 23);
+
+        let state = State::root(&trie).unwrap();
+        assert_eq!(state.stride, 4);
+        let leaf = state.transition(b'a'.into()).unwrap();
+        assert_eq!(leaf.levels().collect::<Vec<_>>(), [(1, 1)]);
+    }
+
+    #[test]
+    fn test_header() {
+        let mut trie = build_trie(TEX).unwrap();
+        assert_eq!(&trie[..5], b"HYPH\x02");
+        assert_eq!(check_header(&trie), Ok(()));
+        assert_eq!(check_header(&trie[..HEADER_LEN]), Err(TrieError::NotATrie));
+        assert_eq!(check_header(&trie[HEADER_LEN..]), Err(TrieError::NotATrie));
+        trie[4] = 0;
+        assert_eq!(check_header(&trie), Err(TrieError::UnsupportedVersion(0)));
+        trie[4] = VERSION + 1;
+        assert_eq!(check_header(&trie), Err(TrieError::UnsupportedVersion(VERSION + 1)));
+    }
+
+    #[test]
+    fn test_header_flags() {
+        let mut builder = TrieBuilder::new();
+        builder.insert_tex(TEX).unwrap();
+        let plain = builder.encode().unwrap();
+        builder.set_chars(true);
+        let chars = builder.encode().unwrap();
+        builder.set_chars(false);
+        builder.set_metadata("English");
+        let metadata = builder.encode().unwrap();
+
+        // Only tries that older readers would misread need the newest version.
+        assert_eq!(plain[4], MIN_VERSION);
+        for trie in [chars, metadata] {
+            assert_eq!(trie[4], VERSION);
+            assert_eq!(check_header(&trie), Ok(()));
+            let mut old = trie.clone();
+            old[4] = MIN_VERSION;
+            assert_eq!(check_header(&old), Err(TrieError::Malformed));
+        }
+
+        for (i, flag, unknown) in
+            [(5, 0x80, 0x0080), (5, 0x08, 0x0008), (6, 0x01, 0x0100)]
+        {
+            let mut trie = plain.clone();
+            trie[i] |= flag;
+            assert_eq!(
+                Trie::from_bytes(&trie),
+                Err(TrieError::UnsupportedFlags(unknown))
+            );
+        }
+        let mut trie = plain;
+        trie[5] |= 0x80;
+        let err = dump_trie(&trie).unwrap_err();
+        assert_eq!(err.to_string(), "unsupported trie format flags 0x0080");
+    }
+
+    #[test]
+    fn test_fuzz_regressions() {
+        // Inputs found by the `dump_trie` fuzz target.
+        for entry in std::fs::read_dir("tests/fixtures/fuzz").unwrap() {
+            let data = std::fs::read(entry.unwrap().path()).unwrap();
+            assert_eq!(dump_trie(&data), Err(TrieError::Malformed));
+            assert!(TrieBuilder::new().verify(&data).is_err());
+            let _ = trie_stats(&data);
+        }
+    }
+
+    #[test]
+    fn test_trie_version() {
+        let mut trie = build_trie(TEX).unwrap();
+        assert_eq!(trie_version(&trie), Some(MIN_VERSION));
+        assert_eq!(trie_version(&trie[..5]), Some(MIN_VERSION));
+        assert_eq!(trie_version(&trie[..4]), None);
+        assert_eq!(trie_version(&trie[1..]), None);
+        assert_eq!(trie_version(b""), None);
+        trie[4] = 1;
+        assert_eq!(trie_version(&trie), Some(1));
+        assert_eq!(Trie::from_bytes(&trie), Err(TrieError::UnsupportedVersion(1)));
+
+        // Readers that only know version 2 can tell that they can't decode
+        // tries using newer features.
+        let mut builder = TrieBuilder::new();
+        builder.insert_tex(TEX).unwrap();
+        builder.set_chars(true);
+        assert_eq!(trie_version(&builder.encode().unwrap()), Some(3));
+        builder.set_chars(false);
+        builder.set_metadata("test");
+        assert_eq!(trie_version(&builder.encode().unwrap()), Some(3));
+        builder.set_metadata("");
+        assert_eq!(trie_version(&builder.encode().unwrap()), Some(2));
+    }
+
+    #[test]
+    fn test_metadata() {
+        let mut builder = TrieBuilder::new();
+        builder.insert_tex(TEX).unwrap();
+        builder.set_checksum(true);
+        let plain = builder.encode().unwrap();
+        assert_eq!(Trie::from_bytes(&plain).unwrap().metadata(), "");
+
+        let metadata = "Français, hyph-fr.tex v1.2";
+        builder.set_metadata(metadata);
+        let trie = builder.encode().unwrap();
+        assert_eq!(trie.len(), plain.len() + 2 + metadata.len());
+        assert_eq!(Trie::from_bytes_checked(&trie).unwrap().metadata(), metadata);
+        for word in ["abcd", "bcda", "dabc"] {
+            assert_eq!(hyphenate(&trie, word), hyphenate(&plain, word));
+        }
+        assert_eq!(dump_trie(&trie).unwrap(), dump_trie(&plain).unwrap());
+
+        // The metadata is covered by the checksum and must be valid UTF-8.
+        let mut corrupted = trie.clone();
+        corrupted[HEADER_LEN + 2] = 0xFF;
+        assert_eq!(
+            Trie::from_bytes_checked(&corrupted),
+            Err(TrieError::ChecksumMismatch)
+        );
+        assert_eq!(Trie::from_bytes(&corrupted), Err(TrieError::Malformed));
+
+        builder.set_metadata(&"a".repeat(MAX_METADATA_LEN + 1));
+        let err = builder.encode().unwrap_err();
+        assert!(matches!(err, BuildError::MetadataTooLong(65536)));
+    }
+
+    #[test]
+    fn test_input() {
+        let dir = std::path::Path::new("tests/fixtures/input");
+        let trie = build_trie_from_file(&dir.join("main.tex")).unwrap();
+        let expected = build_trie(
+            r"\lefthyphenmin=1 \patterns{ 1b 1c 1d } \righthyphenmin 1
+              \patterns{ 1e } \hyphenation{ ab-cd }",
+        )
+        .unwrap();
+        assert_eq!(trie, expected);
+        assert_eq!(stored_bounds(&trie), (1, 1));
+
+        // Without a file, `\input` is ignored.
+        let tex = read_tex_file(&dir.join("main.tex")).unwrap();
+        assert_eq!(hyphenate(&build_trie(&tex).unwrap(), "abcde"), "a-b-cde");
+
+        let files = read_tex_files(&dir.join("main.tex")).unwrap();
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0], tex);
+        assert!(files[2].contains("1e"));
+        assert!(read_tex_files(&dir.join("missing.tex")).is_err());
+
+        // Longer command names only start with `input`.
+        let mut patterns = vec![];
+        parse(
+            r"\inputlineno \input%x
+                \patterns{ 1a }",
+            |pat| patterns.push(pat.to_string()),
+        );
+        assert_eq!(patterns, ["1a"]);
+
+        let err = build_trie_from_file(&dir.join("cycle-a.tex")).unwrap_err();
+        assert!(
+            matches!(&err, BuildError::InputCycle(path) if path.ends_with("cycle-a.tex"))
+        );
+        assert!(err.to_string().ends_with("cycle-a.tex includes itself with \\input"));
+
+        let mut builder = TrieBuilder::new();
+        let err = builder.insert_tex_file(&dir.join("missing.tex")).unwrap_err();
+        assert!(matches!(
+            &err,
+            BuildError::Input { path, error }
+                if path.ends_with("nonexistent.tex") && matches!(**error, BuildError::Io(_))
+        ));
+        assert_eq!(builder.nodes.len(), 1);
+    }
+
+    #[test]
+    fn test_level_out_of_range() {
+        let path = std::path::Path::new("tests/fixtures/broken.tex");
+        let err = build_trie_from_file(path).unwrap_err();
+        assert!(matches!(
+            &err,
+            BuildError::LevelOutOfRange { line: 5, column: 8, level } if level == "10"
+        ));
+        assert_eq!(err.to_string(), "line 5, column 8: level 10 out of range");
+
+        let mut builder = TrieBuilder::new();
+        assert!(builder.insert_tex("\\patterns{ a1b\n% 5q6\n 1ä23 }").is_err());
+        assert_eq!(builder.nodes.len(), 1);
+        assert!(builder.insert_tex("\\patterns{ a1b\n% 56\n 1ä2 }").is_ok());
+    }
+
+    #[test]
+    fn test_level_distance() {
+        let gap = "bcdefghijklmnopqrstuvwxyz";
+        let tex = format!("\\patterns{{\n a1b\n .{gap}2c\n}}");
+        let mut builder = TrieBuilder::new();
+        let err = builder.insert_tex(&tex).unwrap_err();
+        let pattern = format!(".{gap}2c");
+        assert!(matches!(
+            &err,
+            BuildError::PatternDistanceTooLarge { line: 3, column: 28, pattern: p, dist: 26 }
+                if *p == pattern
+        ));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "line 3, column 28: pattern '{pattern}' has inter-letter distance 26 \
+                 which exceeds max 24"
+            )
+        );
+        assert_eq!(builder.nodes.len(), 1);
+
+        // Multi-byte chars count with their length and zeros don't count.
+        assert!(builder
+            .insert_tex(&format!("\\patterns{{ {}1 }}", "ä".repeat(13)))
+            .is_err());
+        assert!(builder
+            .insert_tex(&format!("\\patterns{{ {}1 }}", "ä".repeat(12)))
+            .is_ok());
+        let tex = format!("\\patterns{{ 1{}0{}1 }}", "a".repeat(12), "a".repeat(12));
+        assert!(builder.insert_tex(&tex).is_ok());
+        let tex = format!("\\patterns{{ 1{}0{}1 }}", "a".repeat(12), "a".repeat(13));
+        assert!(builder.insert_tex(&tex).is_err());
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(stored_bounds(&build_trie(TEX).unwrap()), (2, 3));
+        assert_eq!(stored_bounds(&build_trie_with(TEX, 1, 4).unwrap()), (1, 4));
+
+        let tex = r"\lefthyphenmin=1 \righthyphenmin 2 % \righthyphenmin=5";
+        assert_eq!(stored_bounds(&build_trie(tex).unwrap()), (1, 2));
+        assert_eq!(stored_bounds(&build_trie_with(tex, 3, 3).unwrap()), (3, 3));
+    }
+
+    #[test]
+    fn test_bounds_round_trip() {
+        let bytes = build_trie_with(TEX, 1, 4).unwrap();
+        let trie = Trie::from_bytes(&bytes).unwrap();
+        assert_eq!(trie.bounds(), (1, 4));
+        assert_eq!(trie.hyphenate("bbbbb").collect::<Vec<_>>(), ["b", "bbbb"]);
+
+        let bytes = build_trie(TEX).unwrap();
+        assert_eq!(Trie::from_bytes(&bytes).unwrap().bounds(), (2, 3));
+    }
+
+    #[test]
+    fn test_dump() {
+        // Already in the order of the dump. The leaves of `1b`, `1c` and `1d`
+        // are shared and `bc` ends in a node without levels.
+        let tex = r"\lefthyphenmin=1
+\righthyphenmin=2
+\patterns{
+.a1b
+ab1c
+1b
+bc
+1c
+1d
+}
+\hyphenation{
+abc
+ab-cd
+bc-da
+}
+";
+        let trie = build_trie(tex).unwrap();
+        assert_eq!(dump_trie(&trie).unwrap(), tex);
+        assert_eq!(build_trie(&dump_trie(&trie).unwrap()).unwrap(), trie);
+
+        // Once rebuilt from a dump, a trie is in canonical order.
+        let dumped = dump_trie(&build_trie(TEX).unwrap()).unwrap();
+        let rebuilt = build_trie(&dumped).unwrap();
+        assert_eq!(hyphenate(&rebuilt, "BCDA"), "BC-DA");
+        assert_eq!(hyphenate(&rebuilt, "dabc"), "da-b-c");
+        assert_eq!(build_trie(&dump_trie(&rebuilt).unwrap()).unwrap(), rebuilt);
+
+        assert_eq!(dump_trie(b"HYPH"), Err(TrieError::NotATrie));
+    }
+
+    #[test]
+    fn test_dump_english() {
+        let trie = include_bytes!("../tries/en.bin");
+        let rebuilt = build_trie(&dump_trie(trie).unwrap()).unwrap();
+        assert_eq!(dump_trie(&rebuilt).unwrap(), dump_trie(trie).unwrap());
+        assert_eq!(build_trie(&dump_trie(&rebuilt).unwrap()).unwrap(), rebuilt);
+        for word in ["hyphenation", "associate", "extensive", "table"] {
+            assert_eq!(hyphenate(&rebuilt, word), hyphenate(trie, word));
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut builder = TrieBuilder::new();
+        builder.insert("1b");
+        builder.insert("ab1c");
+        builder.set_bounds(1, 4);
+        builder.compress();
+        let trie = builder.encode().unwrap();
+        assert_eq!(
+            trie_stats(&trie),
+            Ok(TrieStats {
+                version: 2,
+                nodes: 5,
+                transitions: 4,
+                levels: 2,
+                size: 32,
+                left_min: 1,
+                right_min: 4,
+                checksum: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_compress2() {
+        let tex = read_tex_file("patterns/hyph-en-us.tex".as_ref()).unwrap();
+        let mut first = TrieBuilder::new();
+        first.insert_tex(&tex).unwrap();
+        let mut second = TrieBuilder::new();
+        second.insert_tex(&tex).unwrap();
+
+        let compression = first.compress();
+        let compression2 = second.compress2();
+        assert_eq!(compression.before, compression2.before);
+        assert!(compression.after < compression.before);
+        assert!(compression2.after <= compression.after);
+
+        let first = first.encode().unwrap();
+        let second = second.encode().unwrap();
+        assert!(second.len() <= first.len());
+        let text = include_str!("../tests/fixtures/batch.txt");
+        for word in text.split_whitespace().chain(["hyphenation", "associate"]) {
+            assert_eq!(hyphenate(&second, word), hyphenate(&first, word));
+        }
+
+        // The nodes after `x` and `y` only differ in the order of their
+        // transitions.
+        let tex = r"\patterns{ xa1 xb1 yb1 ya1 }";
+        let mut first = TrieBuilder::new();
+        first.insert_tex(tex).unwrap();
+        let mut second = TrieBuilder::new();
+        second.insert_tex(tex).unwrap();
+        assert_eq!(first.compress(), Compression { before: 7, after: 4 });
+        assert_eq!(second.compress2(), Compression { before: 7, after: 3 });
+        let (first, second) = (first.encode().unwrap(), second.encode().unwrap());
+        assert_eq!(hyphenate(&second, "xaya"), hyphenate(&first, "xaya"));
+
+        // Compressing again changes nothing.
+        let mut builder = TrieBuilder::new();
+        builder.insert_tex(TEX).unwrap();
+        let Compression { after, .. } = builder.compress();
+        assert_eq!(builder.compress(), Compression { before: after, after });
+        assert_eq!(builder.encode().unwrap(), build_trie(TEX).unwrap());
+    }
+
+    #[test]
+    fn test_uncompressed() {
+        let tex = read_tex_file("patterns/hyph-en-us.tex".as_ref()).unwrap();
+        let mut builder = TrieBuilder::new();
+        builder.insert_tex(&tex).unwrap();
+        let nodes = builder.node_count();
+        let uncompressed = builder.encode().unwrap();
+        let stats = trie_stats(&uncompressed).unwrap();
+        assert_eq!(stats.nodes, nodes);
+
+        let compression = builder.compress();
+        assert_eq!(compression.before, nodes);
+        assert_eq!(builder.node_count(), compression.after);
+        let compressed = builder.encode().unwrap();
+        assert!(compressed.len() < uncompressed.len());
+        builder.verify(&uncompressed).unwrap();
+
+        let text = include_str!("../tests/fixtures/batch.txt");
+        for word in text.split_whitespace().chain(["hyphenation", "associate"]) {
+            assert_eq!(hyphenate(&uncompressed, word), hyphenate(&compressed, word));
+        }
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let tex = read_tex_file("patterns/hyph-en-us.tex".as_ref()).unwrap();
+        let build = |chars: bool, compress: fn(&mut TrieBuilder) -> Compression| {
+            let mut builder = TrieBuilder::new();
+            builder.insert_tex(&tex).unwrap();
+            builder.set_chars(chars);
+            compress(&mut builder);
+            builder.encode().unwrap()
+        };
+
+        // Each builder hashes with different random keys.
+        for chars in [false, true] {
+            let first = build(chars, TrieBuilder::compress);
+            assert_eq!(build(chars, TrieBuilder::compress), first);
+            let first = build(chars, TrieBuilder::compress2);
+            assert_eq!(build(chars, TrieBuilder::compress2), first);
+        }
+        assert_eq!(build(false, TrieBuilder::compress), build_trie(&tex).unwrap());
+    }
+
+    #[test]
+    fn test_size_budget() {
+        let tex = read_tex_file("tests/fixtures/dump.tex".as_ref()).unwrap();
+        let (trie, stats) = build_and_measure(&tex).unwrap();
+        assert_eq!(trie, build_trie(&tex).unwrap());
+        assert_eq!(stats, trie_stats(&trie).unwrap());
+        assert!(stats.size <= 100, "trie has grown to {} bytes", stats.size);
+
+        assert!(build_and_measure(r"\patterns{ a10b }").is_err());
+    }
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(crc32(b"123456789"), 0xCBF43926);
+
+        let mut builder = TrieBuilder::new();
+        builder.insert_tex(TEX).unwrap();
+        builder.set_checksum(true);
+        builder.compress();
+        let mut trie = builder.encode().unwrap();
+        assert_eq!(check_checksum(&trie), Ok(()));
+        assert_eq!(hyphenate(&trie, "abcd"), "ab-cd");
+        assert!(trie_stats(&trie).unwrap().checksum);
+
+        let last = trie.len() - 5;
+        trie[last] ^= 1;
+        assert_eq!(check_header(&trie), Ok(()));
+        assert_eq!(check_checksum(&trie), Err(TrieError::ChecksumMismatch));
+
+        let trie = build_trie(TEX).unwrap();
+        assert_eq!(check_checksum(&trie), Err(TrieError::MissingChecksum));
+    }
+
+    #[test]
+    #[cfg(feature = "compress")]
+    fn test_compressed() {
+        use super::build_trie_from_file;
+        use std::path::Path;
+
+        let plain = build_trie_from_file(Path::new("tests/fixtures/dump.tex")).unwrap();
+        let gz = build_trie_from_file(Path::new("tests/fixtures/dump.tex.gz")).unwrap();
+        assert_eq!(gz, plain);
+
+        let tex = std::fs::read("tests/fixtures/dump.tex").unwrap();
+        let path = std::env::temp_dir().join("hypher-test-dump.tex.zst");
+        std::fs::write(&path, zstd::encode_all(tex.as_slice(), 0).unwrap()).unwrap();
+        assert_eq!(build_trie_from_file(&path).unwrap(), plain);
+    }
+
+    #[test]
+    fn test_merge() {
+        let first = build_trie(r"\patterns{ 1b 1c } \hyphenation{ ab-cd }").unwrap();
+        let second = build_trie(r"\patterns{ 1x 2c } \hyphenation{ xy-z }").unwrap();
+        let merged = merge_tries(&[&first, &second]).unwrap();
+        assert_eq!(hyphenate(&merged, "abxb"), "a-b-x-b");
+        assert_eq!(hyphenate(&merged, "abc"), "a-bc");
+        assert_eq!(hyphenate(&merged, "abcd"), "ab-cd");
+        assert_eq!(hyphenate(&merged, "xyz"), "xy-z");
+
+        let merged = merge_tries(&[&second, &first]).unwrap();
+        assert_eq!(hyphenate(&merged, "abc"), "a-b-c");
+
+        let err = merge_tries(&[&first, b"HYPH"]).unwrap_err();
+        assert!(matches!(err, BuildError::Trie(TrieError::NotATrie)));
+    }
+
+    #[test]
+    fn test_level_inside_char() {
+        // The level between the two bytes of `ä` must be ignored.
+        let mut builder = TrieBuilder::new();
+        builder.insert_path("ä".as_bytes(), &[(1, 1)], false);
+        builder.insert("1b");
+        builder.compress();
+        let trie = builder.encode().unwrap();
+        assert_eq!(hyphenate(&trie, "aäbä"), "aä-bä");
+    }
+}
@@ -0,0 +1,107 @@
+//! Bindings for C.
+//!
+//! This module exposes hyphenation with dynamically loaded tries to C.
+//!
+//! Build a static or dynamic library with
+//! `cargo rustc --release --features ffi --crate-type staticlib` (or
+//! `cdylib`). The functions and the opaque [`HypherTrie`] type are laid out
+//! such that `cbindgen` can generate a header for them.
+//!
+//! All functions accept null pointers and treat them as documented instead of
+//! crashing. Any other pointer must be valid as described for each function.
+#![allow(unsafe_code)]
+
+use alloc::boxed::Box;
+use core::ffi::{c_char, CStr};
+
+use crate::Trie;
+
+/// A trie loaded with [`hypher_trie_new`].
+///
+/// The handle owns a copy of the trie's bytes. It must be freed with
+/// [`hypher_trie_free`].
+pub struct HypherTrie {
+    data: Box<[u8]>,
+}
+
+/// Load a trie from `len` bytes at `data`.
+///
+/// The bytes are copied, so the caller keeps ownership of `data` and may free
+/// it right after this call. Returns null if `data` is null or the bytes are
+/// not a trie, as checked by [`Trie::from_bytes`].
+///
+/// # Safety
+/// Unless null, `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn hypher_trie_new(data: *const u8, len: usize) -> *mut HypherTrie {
+    if data.is_null() {
+        return core::ptr::null_mut();
+    }
+
+    // SAFETY: The caller guarantees that `data` points to `len` bytes.
+    let bytes = unsafe { core::slice::from_raw_parts(data, len) };
+    if Trie::from_bytes(bytes).is_err() {
+        return core::ptr::null_mut();
+    }
+
+    Box::into_raw(Box::new(HypherTrie { data: bytes.into() }))
+}
+
+/// Hyphenate the nul-terminated UTF-8 string `word` with the trie's bounds.
+///
+/// Writes the byte offsets at which the word may be broken into `out`, in
+/// ascending order and at most `cap` of them. Returns the total number of
+/// possible breaks, which is larger than `cap` if not all of them fit. Call
+/// again with a larger buffer in that case. Returns -1 if `trie` or `word` is
+/// null or `word` is not valid UTF-8, without writing anything.
+///
+/// # Safety
+/// Unless null, `trie` must be a handle returned by [`hypher_trie_new`] that
+/// was not freed yet and `word` must point to a nul-terminated string. `out`
+/// must point to space for `cap` offsets unless `cap` is zero, in which case
+/// it may be null.
+#[no_mangle]
+pub unsafe extern "C" fn hypher_hyphenate(
+    trie: *const HypherTrie,
+    word: *const c_char,
+    out: *mut usize,
+    cap: usize,
+) -> isize {
+    if trie.is_null() || word.is_null() {
+        return -1;
+    }
+
+    // SAFETY: The caller guarantees that `trie` is a live handle and `word` a
+    // nul-terminated string.
+    let (trie, word) = unsafe { (&*trie, CStr::from_ptr(word)) };
+    let word = match word.to_str() {
+        Ok(word) => word,
+        Err(_) => return -1,
+    };
+
+    let positions = Trie { data: &trie.data }.hyphenate(word).into_positions();
+    let count = positions.len();
+    for (i, position) in positions.take(cap).enumerate() {
+        // SAFETY: The caller guarantees that `out` has space for `cap`
+        // offsets and `i` is less than `cap`.
+        unsafe { out.add(i).write(position) };
+    }
+
+    count as isize
+}
+
+/// Free a trie loaded with [`hypher_trie_new`].
+///
+/// Does nothing if `trie` is null.
+///
+/// # Safety
+/// Unless null, `trie` must be a handle returned by [`hypher_trie_new`] that
+/// was not freed yet. It must not be used anymore afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn hypher_trie_free(trie: *mut HypherTrie) {
+    if !trie.is_null() {
+        // SAFETY: The caller guarantees that `trie` is a live handle, which
+        // was created by `Box::into_raw`.
+        drop(unsafe { Box::from_raw(trie) });
+    }
+}
@@ -124,12 +124,7 @@ pub enum Lang<'a> {
     Ukrainian,
     /// Dynamically loaded patterns.
     #[cfg(feature = "dyn")]
-    Dyn {
-        /// See method `bounds`.
-        bounds: (usize, usize),
-        /// See method `root`.
-        bytes: &'a [u8],
-    },
+    Dyn(Trie<'a>),
     /// Cannot be constructed.
     /// Silences the unused lifetime warning.
     #[cfg(not(feature = "dyn"))]
@@ -217,27 +212,568 @@ impl Lang<'static> {
             _ => None,
         }
     }
+
+    /// Select a language using its English or native name,
+    /// like `"German"` or `"Deutsch"`, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let names: &[(&str, Self)] = &[
+            #[cfg(feature = "afrikaans")]
+            ("Afrikaans", Self::Afrikaans),
+            #[cfg(feature = "albanian")]
+            ("Albanian", Self::Albanian),
+            #[cfg(feature = "albanian")]
+            ("Shqip", Self::Albanian),
+            #[cfg(feature = "belarusian")]
+            ("Belarusian", Self::Belarusian),
+            #[cfg(feature = "belarusian")]
+            ("Беларуская", Self::Belarusian),
+            #[cfg(feature = "bulgarian")]
+            ("Bulgarian", Self::Bulgarian),
+            #[cfg(feature = "bulgarian")]
+            ("Български", Self::Bulgarian),
+            #[cfg(feature = "catalan")]
+            ("Catalan", Self::Catalan),
+            #[cfg(feature = "catalan")]
+            ("Català", Self::Catalan),
+            #[cfg(feature = "croatian")]
+            ("Croatian", Self::Croatian),
+            #[cfg(feature = "croatian")]
+            ("Hrvatski", Self::Croatian),
+            #[cfg(feature = "czech")]
+            ("Czech", Self::Czech),
+            #[cfg(feature = "czech")]
+            ("Čeština", Self::Czech),
+            #[cfg(feature = "danish")]
+            ("Danish", Self::Danish),
+            #[cfg(feature = "danish")]
+            ("Dansk", Self::Danish),
+            #[cfg(feature = "dutch")]
+            ("Dutch", Self::Dutch),
+            #[cfg(feature = "dutch")]
+            ("Nederlands", Self::Dutch),
+            #[cfg(feature = "english")]
+            ("English", Self::English),
+            #[cfg(feature = "estonian")]
+            ("Estonian", Self::Estonian),
+            #[cfg(feature = "estonian")]
+            ("Eesti", Self::Estonian),
+            #[cfg(feature = "finnish")]
+            ("Finnish", Self::Finnish),
+            #[cfg(feature = "finnish")]
+            ("Suomi", Self::Finnish),
+            #[cfg(feature = "french")]
+            ("French", Self::French),
+            #[cfg(feature = "french")]
+            ("Français", Self::French),
+            #[cfg(feature = "georgian")]
+            ("Georgian", Self::Georgian),
+            #[cfg(feature = "georgian")]
+            ("ქართული", Self::Georgian),
+            #[cfg(feature = "german")]
+            ("German", Self::German),
+            #[cfg(feature = "german")]
+            ("Deutsch", Self::German),
+            #[cfg(feature = "greek")]
+            ("Greek", Self::Greek),
+            #[cfg(feature = "greek")]
+            ("Ελληνικά", Self::Greek),
+            #[cfg(feature = "hungarian")]
+            ("Hungarian", Self::Hungarian),
+            #[cfg(feature = "hungarian")]
+            ("Magyar", Self::Hungarian),
+            #[cfg(feature = "icelandic")]
+            ("Icelandic", Self::Icelandic),
+            #[cfg(feature = "icelandic")]
+            ("Íslenska", Self::Icelandic),
+            #[cfg(feature = "italian")]
+            ("Italian", Self::Italian),
+            #[cfg(feature = "italian")]
+            ("Italiano", Self::Italian),
+            #[cfg(feature = "kurmanji")]
+            ("Kurmanji", Self::Kurmanji),
+            #[cfg(feature = "kurmanji")]
+            ("Kurmancî", Self::Kurmanji),
+            #[cfg(feature = "latin")]
+            ("Latin", Self::Latin),
+            #[cfg(feature = "latin")]
+            ("Latina", Self::Latin),
+            #[cfg(feature = "lithuanian")]
+            ("Lithuanian", Self::Lithuanian),
+            #[cfg(feature = "lithuanian")]
+            ("Lietuvių", Self::Lithuanian),
+            #[cfg(feature = "mongolian")]
+            ("Mongolian", Self::Mongolian),
+            #[cfg(feature = "mongolian")]
+            ("Монгол", Self::Mongolian),
+            #[cfg(feature = "norwegian")]
+            ("Norwegian", Self::Norwegian),
+            #[cfg(feature = "norwegian")]
+            ("Norsk", Self::Norwegian),
+            #[cfg(feature = "norwegian")]
+            ("Bokmål", Self::Norwegian),
+            #[cfg(feature = "norwegian")]
+            ("Nynorsk", Self::Norwegian),
+            #[cfg(feature = "polish")]
+            ("Polish", Self::Polish),
+            #[cfg(feature = "polish")]
+            ("Polski", Self::Polish),
+            #[cfg(feature = "portuguese")]
+            ("Portuguese", Self::Portuguese),
+            #[cfg(feature = "portuguese")]
+            ("Português", Self::Portuguese),
+            #[cfg(feature = "russian")]
+            ("Russian", Self::Russian),
+            #[cfg(feature = "russian")]
+            ("Русский", Self::Russian),
+            #[cfg(feature = "serbian")]
+            ("Serbian", Self::Serbian),
+            #[cfg(feature = "serbian")]
+            ("Српски", Self::Serbian),
+            #[cfg(feature = "slovak")]
+            ("Slovak", Self::Slovak),
+            #[cfg(feature = "slovak")]
+            ("Slovenčina", Self::Slovak),
+            #[cfg(feature = "slovenian")]
+            ("Slovenian", Self::Slovenian),
+            #[cfg(feature = "slovenian")]
+            ("Slovenščina", Self::Slovenian),
+            #[cfg(feature = "spanish")]
+            ("Spanish", Self::Spanish),
+            #[cfg(feature = "spanish")]
+            ("Español", Self::Spanish),
+            #[cfg(feature = "swedish")]
+            ("Swedish", Self::Swedish),
+            #[cfg(feature = "swedish")]
+            ("Svenska", Self::Swedish),
+            #[cfg(feature = "turkish")]
+            ("Turkish", Self::Turkish),
+            #[cfg(feature = "turkish")]
+            ("Türkçe", Self::Turkish),
+            #[cfg(feature = "turkmen")]
+            ("Turkmen", Self::Turkmen),
+            #[cfg(feature = "turkmen")]
+            ("Türkmençe", Self::Turkmen),
+            #[cfg(feature = "ukrainian")]
+            ("Ukrainian", Self::Ukrainian),
+            #[cfg(feature = "ukrainian")]
+            ("Українська", Self::Ukrainian),
+        ];
+        names
+            .iter()
+            .find(|(alias, _)| eq_ignore_case(alias, name))
+            .map(|&(_, lang)| lang)
+    }
+
+    /// All languages whose features are enabled, in alphabetical
+    /// order of their names.
+    pub fn all() -> &'static [Self] {
+        &[
+            #[cfg(feature = "afrikaans")]
+            Self::Afrikaans,
+            #[cfg(feature = "albanian")]
+            Self::Albanian,
+            #[cfg(feature = "belarusian")]
+            Self::Belarusian,
+            #[cfg(feature = "bulgarian")]
+            Self::Bulgarian,
+            #[cfg(feature = "catalan")]
+            Self::Catalan,
+            #[cfg(feature = "croatian")]
+            Self::Croatian,
+            #[cfg(feature = "czech")]
+            Self::Czech,
+            #[cfg(feature = "danish")]
+            Self::Danish,
+            #[cfg(feature = "dutch")]
+            Self::Dutch,
+            #[cfg(feature = "english")]
+            Self::English,
+            #[cfg(feature = "estonian")]
+            Self::Estonian,
+            #[cfg(feature = "finnish")]
+            Self::Finnish,
+            #[cfg(feature = "french")]
+            Self::French,
+            #[cfg(feature = "georgian")]
+            Self::Georgian,
+            #[cfg(feature = "german")]
+            Self::German,
+            #[cfg(feature = "greek")]
+            Self::Greek,
+            #[cfg(feature = "hungarian")]
+            Self::Hungarian,
+            #[cfg(feature = "icelandic")]
+            Self::Icelandic,
+            #[cfg(feature = "italian")]
+            Self::Italian,
+            #[cfg(feature = "kurmanji")]
+            Self::Kurmanji,
+            #[cfg(feature = "latin")]
+            Self::Latin,
+            #[cfg(feature = "lithuanian")]
+            Self::Lithuanian,
+            #[cfg(feature = "mongolian")]
+            Self::Mongolian,
+            #[cfg(feature = "norwegian")]
+            Self::Norwegian,
+            #[cfg(feature = "polish")]
+            Self::Polish,
+            #[cfg(feature = "portuguese")]
+            Self::Portuguese,
+            #[cfg(feature = "russian")]
+            Self::Russian,
+            #[cfg(feature = "serbian")]
+            Self::Serbian,
+            #[cfg(feature = "slovak")]
+            Self::Slovak,
+            #[cfg(feature = "slovenian")]
+            Self::Slovenian,
+            #[cfg(feature = "spanish")]
+            Self::Spanish,
+            #[cfg(feature = "swedish")]
+            Self::Swedish,
+            #[cfg(feature = "turkish")]
+            Self::Turkish,
+            #[cfg(feature = "turkmen")]
+            Self::Turkmen,
+            #[cfg(feature = "ukrainian")]
+            Self::Ukrainian,
+        ]
+    }
+
+    /// An iterator over all languages whose features are enabled.
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::all().iter().copied()
+    }
 }
 
 impl<'a> Lang<'a> {
     /// Dynamically load new patterns.
-    /// No validation will occur here: if you provide a malformed
-    /// automata the program might panic when you try to use it.
+    ///
+    /// This is a shorthand for wrapping `Trie::from_bytes`.
+    ///
+    /// Pass as `bytes` the output of `hypher::builder::build_trie`
+    /// or an equivalently obtained well-formed trie. Its stored
+    /// (left,right)-hyphenmin become the language's `bounds`.
+    ///
+    /// Fails if `bytes` don't start with the header of a trie of
+    /// a supported format version or if its metadata or root node
+    /// can't be decoded. No further validation will occur here: if
+    /// you provide a malformed automata, hyphenation won't panic
+    /// but may yield nonsensical syllables.
+    ///
+    /// Tries built by hypher 0.1 have no header and the bounds
+    /// had to be passed here instead. Rebuild them from their
+    /// pattern file, with `hypher::builder::build_trie_with`
+    /// to store those bounds in the trie.
     #[cfg(feature = "dyn")]
-    pub fn from_bytes(
-        /// (left,right)-hyphenmin of the language.
-        bounds: (usize, usize),
-        /// Pass to this the output of `hypher::builder::build_trie`
-        /// or an equivalently obtained well-formed trie.
-        bytes: &'a [u8],
-    ) -> Self {
-        Self::Dyn { bounds, bytes }
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, TrieError> {
+        Trie::from_bytes(bytes).map(Self::Dyn)
+    }
+
+    /// The ISO 639-1 code of the language.
+    ///
+    /// Returns `None` for dynamically loaded patterns. For
+    /// languages with aliases, this is the main code.
+    pub fn iso(&self) -> Option<[u8; 2]> {
+        match self {
+            #[cfg(feature = "afrikaans")]
+            Self::Afrikaans => Some(*b"af"),
+            #[cfg(feature = "albanian")]
+            Self::Albanian => Some(*b"sq"),
+            #[cfg(feature = "belarusian")]
+            Self::Belarusian => Some(*b"be"),
+            #[cfg(feature = "bulgarian")]
+            Self::Bulgarian => Some(*b"bg"),
+            #[cfg(feature = "catalan")]
+            Self::Catalan => Some(*b"ca"),
+            #[cfg(feature = "croatian")]
+            Self::Croatian => Some(*b"hr"),
+            #[cfg(feature = "czech")]
+            Self::Czech => Some(*b"cs"),
+            #[cfg(feature = "danish")]
+            Self::Danish => Some(*b"da"),
+            #[cfg(feature = "dutch")]
+            Self::Dutch => Some(*b"nl"),
+            #[cfg(feature = "english")]
+            Self::English => Some(*b"en"),
+            #[cfg(feature = "estonian")]
+            Self::Estonian => Some(*b"et"),
+            #[cfg(feature = "finnish")]
+            Self::Finnish => Some(*b"fi"),
+            #[cfg(feature = "french")]
+            Self::French => Some(*b"fr"),
+            #[cfg(feature = "georgian")]
+            Self::Georgian => Some(*b"ka"),
+            #[cfg(feature = "german")]
+            Self::German => Some(*b"de"),
+            #[cfg(feature = "greek")]
+            Self::Greek => Some(*b"el"),
+            #[cfg(feature = "hungarian")]
+            Self::Hungarian => Some(*b"hu"),
+            #[cfg(feature = "icelandic")]
+            Self::Icelandic => Some(*b"is"),
+            #[cfg(feature = "italian")]
+            Self::Italian => Some(*b"it"),
+            #[cfg(feature = "kurmanji")]
+            Self::Kurmanji => Some(*b"ku"),
+            #[cfg(feature = "latin")]
+            Self::Latin => Some(*b"la"),
+            #[cfg(feature = "lithuanian")]
+            Self::Lithuanian => Some(*b"lt"),
+            #[cfg(feature = "mongolian")]
+            Self::Mongolian => Some(*b"mn"),
+            #[cfg(feature = "norwegian")]
+            Self::Norwegian => Some(*b"no"),
+            #[cfg(feature = "polish")]
+            Self::Polish => Some(*b"pl"),
+            #[cfg(feature = "portuguese")]
+            Self::Portuguese => Some(*b"pt"),
+            #[cfg(feature = "russian")]
+            Self::Russian => Some(*b"ru"),
+            #[cfg(feature = "serbian")]
+            Self::Serbian => Some(*b"sr"),
+            #[cfg(feature = "slovak")]
+            Self::Slovak => Some(*b"sk"),
+            #[cfg(feature = "slovenian")]
+            Self::Slovenian => Some(*b"sl"),
+            #[cfg(feature = "spanish")]
+            Self::Spanish => Some(*b"es"),
+            #[cfg(feature = "swedish")]
+            Self::Swedish => Some(*b"sv"),
+            #[cfg(feature = "turkish")]
+            Self::Turkish => Some(*b"tr"),
+            #[cfg(feature = "turkmen")]
+            Self::Turkmen => Some(*b"tk"),
+            #[cfg(feature = "ukrainian")]
+            Self::Ukrainian => Some(*b"uk"),
+            #[cfg(feature = "dyn")]
+            Self::Dyn(_) => None,
+            #[cfg(not(feature = "dyn"))]
+            Self::Absurd(_) => unreachable!(),
+        }
+    }
+
+    /// The English name of the language.
+    ///
+    /// Returns `None` for dynamically loaded patterns.
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            #[cfg(feature = "afrikaans")]
+            Self::Afrikaans => Some("Afrikaans"),
+            #[cfg(feature = "albanian")]
+            Self::Albanian => Some("Albanian"),
+            #[cfg(feature = "belarusian")]
+            Self::Belarusian => Some("Belarusian"),
+            #[cfg(feature = "bulgarian")]
+            Self::Bulgarian => Some("Bulgarian"),
+            #[cfg(feature = "catalan")]
+            Self::Catalan => Some("Catalan"),
+            #[cfg(feature = "croatian")]
+            Self::Croatian => Some("Croatian"),
+            #[cfg(feature = "czech")]
+            Self::Czech => Some("Czech"),
+            #[cfg(feature = "danish")]
+            Self::Danish => Some("Danish"),
+            #[cfg(feature = "dutch")]
+            Self::Dutch => Some("Dutch"),
+            #[cfg(feature = "english")]
+            Self::English => Some("English"),
+            #[cfg(feature = "estonian")]
+            Self::Estonian => Some("Estonian"),
+            #[cfg(feature = "finnish")]
+            Self::Finnish => Some("Finnish"),
+            #[cfg(feature = "french")]
+            Self::French => Some("French"),
+            #[cfg(feature = "georgian")]
+            Self::Georgian => Some("Georgian"),
+            #[cfg(feature = "german")]
+            Self::German => Some("German"),
+            #[cfg(feature = "greek")]
+            Self::Greek => Some("Greek"),
+            #[cfg(feature = "hungarian")]
+            Self::Hungarian => Some("Hungarian"),
+            #[cfg(feature = "icelandic")]
+            Self::Icelandic => Some("Icelandic"),
+            #[cfg(feature = "italian")]
+            Self::Italian => Some("Italian"),
+            #[cfg(feature = "kurmanji")]
+            Self::Kurmanji => Some("Kurmanji"),
+            #[cfg(feature = "latin")]
+            Self::Latin => Some("Latin"),
+            #[cfg(feature = "lithuanian")]
+            Self::Lithuanian => Some("Lithuanian"),
+            #[cfg(feature = "mongolian")]
+            Self::Mongolian => Some("Mongolian"),
+            #[cfg(feature = "norwegian")]
+            Self::Norwegian => Some("Norwegian"),
+            #[cfg(feature = "polish")]
+            Self::Polish => Some("Polish"),
+            #[cfg(feature = "portuguese")]
+            Self::Portuguese => Some("Portuguese"),
+            #[cfg(feature = "russian")]
+            Self::Russian => Some("Russian"),
+            #[cfg(feature = "serbian")]
+            Self::Serbian => Some("Serbian"),
+            #[cfg(feature = "slovak")]
+            Self::Slovak => Some("Slovak"),
+            #[cfg(feature = "slovenian")]
+            Self::Slovenian => Some("Slovenian"),
+            #[cfg(feature = "spanish")]
+            Self::Spanish => Some("Spanish"),
+            #[cfg(feature = "swedish")]
+            Self::Swedish => Some("Swedish"),
+            #[cfg(feature = "turkish")]
+            Self::Turkish => Some("Turkish"),
+            #[cfg(feature = "turkmen")]
+            Self::Turkmen => Some("Turkmen"),
+            #[cfg(feature = "ukrainian")]
+            Self::Ukrainian => Some("Ukrainian"),
+            #[cfg(feature = "dyn")]
+            Self::Dyn(_) => None,
+            #[cfg(not(feature = "dyn"))]
+            Self::Absurd(_) => unreachable!(),
+        }
+    }
+
+    /// The script the language is written in.
+    ///
+    /// Returns `None` for dynamically loaded patterns.
+    pub fn script(&self) -> Option<Script> {
+        match self {
+            #[cfg(feature = "afrikaans")]
+            Self::Afrikaans => Some(Script::Latin),
+            #[cfg(feature = "albanian")]
+            Self::Albanian => Some(Script::Latin),
+            #[cfg(feature = "belarusian")]
+            Self::Belarusian => Some(Script::Cyrillic),
+            #[cfg(feature = "bulgarian")]
+            Self::Bulgarian => Some(Script::Cyrillic),
+            #[cfg(feature = "catalan")]
+            Self::Catalan => Some(Script::Latin),
+            #[cfg(feature = "croatian")]
+            Self::Croatian => Some(Script::Latin),
+            #[cfg(feature = "czech")]
+            Self::Czech => Some(Script::Latin),
+            #[cfg(feature = "danish")]
+            Self::Danish => Some(Script::Latin),
+            #[cfg(feature = "dutch")]
+            Self::Dutch => Some(Script::Latin),
+            #[cfg(feature = "english")]
+            Self::English => Some(Script::Latin),
+            #[cfg(feature = "estonian")]
+            Self::Estonian => Some(Script::Latin),
+            #[cfg(feature = "finnish")]
+            Self::Finnish => Some(Script::Latin),
+            #[cfg(feature = "french")]
+            Self::French => Some(Script::Latin),
+            #[cfg(feature = "georgian")]
+            Self::Georgian => Some(Script::Georgian),
+            #[cfg(feature = "german")]
+            Self::German => Some(Script::Latin),
+            #[cfg(feature = "greek")]
+            Self::Greek => Some(Script::Greek),
+            #[cfg(feature = "hungarian")]
+            Self::Hungarian => Some(Script::Latin),
+            #[cfg(feature = "icelandic")]
+            Self::Icelandic => Some(Script::Latin),
+            #[cfg(feature = "italian")]
+            Self::Italian => Some(Script::Latin),
+            #[cfg(feature = "kurmanji")]
+            Self::Kurmanji => Some(Script::Latin),
+            #[cfg(feature = "latin")]
+            Self::Latin => Some(Script::Latin),
+            #[cfg(feature = "lithuanian")]
+            Self::Lithuanian => Some(Script::Latin),
+            #[cfg(feature = "mongolian")]
+            Self::Mongolian => Some(Script::Cyrillic),
+            #[cfg(feature = "norwegian")]
+            Self::Norwegian => Some(Script::Latin),
+            #[cfg(feature = "polish")]
+            Self::Polish => Some(Script::Latin),
+            #[cfg(feature = "portuguese")]
+            Self::Portuguese => Some(Script::Latin),
+            #[cfg(feature = "russian")]
+            Self::Russian => Some(Script::Cyrillic),
+            #[cfg(feature = "serbian")]
+            Self::Serbian => Some(Script::Cyrillic),
+            #[cfg(feature = "slovak")]
+            Self::Slovak => Some(Script::Latin),
+            #[cfg(feature = "slovenian")]
+            Self::Slovenian => Some(Script::Latin),
+            #[cfg(feature = "spanish")]
+            Self::Spanish => Some(Script::Latin),
+            #[cfg(feature = "swedish")]
+            Self::Swedish => Some(Script::Latin),
+            #[cfg(feature = "turkish")]
+            Self::Turkish => Some(Script::Latin),
+            #[cfg(feature = "turkmen")]
+            Self::Turkmen => Some(Script::Latin),
+            #[cfg(feature = "ukrainian")]
+            Self::Ukrainian => Some(Script::Cyrillic),
+            #[cfg(feature = "dyn")]
+            Self::Dyn(_) => None,
+            #[cfg(not(feature = "dyn"))]
+            Self::Absurd(_) => unreachable!(),
+        }
+    }
+
+    /// Dynamically load new patterns, verifying their checksum.
+    ///
+    /// Like `from_bytes`, but also fails if the trie doesn't
+    /// end with a checksum or it doesn't match the data. Build
+    /// the trie with `TrieBuilder::set_checksum` to add one.
+    #[cfg(feature = "dyn")]
+    pub fn from_bytes_checked(bytes: &'a [u8]) -> Result<Self, TrieError> {
+        Trie::from_bytes_checked(bytes).map(Self::Dyn)
     }
 
     /// The default number of chars to each side between
     /// which breaking is forbidden.
     ///
-    /// This follows typographic conventions.
+    /// This follows typographic conventions. [`hyphenate`] uses
+    /// these bounds, [`hyphenate_bounded`] overrides them. For
+    /// dynamically loaded patterns, they are the
+    /// (left,right)-hyphenmin stored in the trie. For the
+    /// built-in languages, they are:
+    ///
+    /// | Language | Left | Right |
+    /// |----------|------|-------|
+    /// | Afrikaans | 1 | 2 |
+    /// | Albanian | 2 | 2 |
+    /// | Belarusian | 2 | 2 |
+    /// | Bulgarian | 2 | 2 |
+    /// | Catalan | 2 | 2 |
+    /// | Croatian | 2 | 2 |
+    /// | Czech | 2 | 2 |
+    /// | Danish | 2 | 2 |
+    /// | Dutch | 2 | 2 |
+    /// | English | 2 | 3 |
+    /// | Estonian | 2 | 3 |
+    /// | Finnish | 2 | 2 |
+    /// | French | 2 | 2 |
+    /// | Georgian | 1 | 2 |
+    /// | German | 2 | 2 |
+    /// | Greek | 1 | 1 |
+    /// | Hungarian | 2 | 2 |
+    /// | Icelandic | 2 | 2 |
+    /// | Italian | 2 | 2 |
+    /// | Kurmanji | 2 | 2 |
+    /// | Latin | 2 | 2 |
+    /// | Lithuanian | 2 | 2 |
+    /// | Mongolian | 2 | 2 |
+    /// | Norwegian | 2 | 2 |
+    /// | Polish | 2 | 2 |
+    /// | Portuguese | 2 | 3 |
+    /// | Russian | 2 | 2 |
+    /// | Serbian | 2 | 2 |
+    /// | Slovak | 2 | 3 |
+    /// | Slovenian | 2 | 2 |
+    /// | Spanish | 2 | 2 |
+    /// | Swedish | 2 | 2 |
+    /// | Turkish | 2 | 2 |
+    /// | Turkmen | 2 | 2 |
+    /// | Ukrainian | 2 | 2 |
     pub fn bounds(self) -> (usize, usize) {
         match self {
             #[cfg(feature = "afrikaans")]
@@ -311,87 +847,87 @@ impl<'a> Lang<'a> {
             #[cfg(feature = "ukrainian")]
             Self::Ukrainian => (2, 2),
             #[cfg(feature = "dyn")]
-            Self::Dyn { bounds, .. } => bounds,
+            Self::Dyn(trie) => trie.bounds(),
             #[cfg(not(feature = "dyn"))]
             Self::Absurd(_) => unreachable!(),
         }
     }
 
-    /// Entry point of the automata.
-    fn root(self) -> State<'a> {
+    /// The trie with the patterns of the language.
+    pub fn trie(self) -> Trie<'a> {
         match self {
             #[cfg(feature = "afrikaans")]
-            Self::Afrikaans => State::root(include_bytes!("../tries/af.bin")),
+            Self::Afrikaans => Trie { data: include_bytes!("../tries/af.bin") },
             #[cfg(feature = "albanian")]
-            Self::Albanian => State::root(include_bytes!("../tries/sq.bin")),
+            Self::Albanian => Trie { data: include_bytes!("../tries/sq.bin") },
             #[cfg(feature = "belarusian")]
-            Self::Belarusian => State::root(include_bytes!("../tries/be.bin")),
+            Self::Belarusian => Trie { data: include_bytes!("../tries/be.bin") },
             #[cfg(feature = "bulgarian")]
-            Self::Bulgarian => State::root(include_bytes!("../tries/bg.bin")),
+            Self::Bulgarian => Trie { data: include_bytes!("../tries/bg.bin") },
             #[cfg(feature = "catalan")]
-            Self::Catalan => State::root(include_bytes!("../tries/ca.bin")),
+            Self::Catalan => Trie { data: include_bytes!("../tries/ca.bin") },
             #[cfg(feature = "croatian")]
-            Self::Croatian => State::root(include_bytes!("../tries/hr.bin")),
+            Self::Croatian => Trie { data: include_bytes!("../tries/hr.bin") },
             #[cfg(feature = "czech")]
-            Self::Czech => State::root(include_bytes!("../tries/cs.bin")),
+            Self::Czech => Trie { data: include_bytes!("../tries/cs.bin") },
             #[cfg(feature = "danish")]
-            Self::Danish => State::root(include_bytes!("../tries/da.bin")),
+            Self::Danish => Trie { data: include_bytes!("../tries/da.bin") },
             #[cfg(feature = "dutch")]
-            Self::Dutch => State::root(include_bytes!("../tries/nl.bin")),
+            Self::Dutch => Trie { data: include_bytes!("../tries/nl.bin") },
             #[cfg(feature = "english")]
-            Self::English => State::root(include_bytes!("../tries/en.bin")),
+            Self::English => Trie { data: include_bytes!("../tries/en.bin") },
             #[cfg(feature = "estonian")]
-            Self::Estonian => State::root(include_bytes!("../tries/et.bin")),
+            Self::Estonian => Trie { data: include_bytes!("../tries/et.bin") },
             #[cfg(feature = "finnish")]
-            Self::Finnish => State::root(include_bytes!("../tries/fi.bin")),
+            Self::Finnish => Trie { data: include_bytes!("../tries/fi.bin") },
             #[cfg(feature = "french")]
-            Self::French => State::root(include_bytes!("../tries/fr.bin")),
+            Self::French => Trie { data: include_bytes!("../tries/fr.bin") },
             #[cfg(feature = "georgian")]
-            Self::Georgian => State::root(include_bytes!("../tries/ka.bin")),
+            Self::Georgian => Trie { data: include_bytes!("../tries/ka.bin") },
             #[cfg(feature = "german")]
-            Self::German => State::root(include_bytes!("../tries/de.bin")),
+            Self::German => Trie { data: include_bytes!("../tries/de.bin") },
             #[cfg(feature = "greek")]
-            Self::Greek => State::root(include_bytes!("../tries/el.bin")),
+            Self::Greek => Trie { data: include_bytes!("../tries/el.bin") },
             #[cfg(feature = "hungarian")]
-            Self::Hungarian => State::root(include_bytes!("../tries/hu.bin")),
+            Self::Hungarian => Trie { data: include_bytes!("../tries/hu.bin") },
             #[cfg(feature = "icelandic")]
-            Self::Icelandic => State::root(include_bytes!("../tries/is.bin")),
+            Self::Icelandic => Trie { data: include_bytes!("../tries/is.bin") },
             #[cfg(feature = "italian")]
-            Self::Italian => State::root(include_bytes!("../tries/it.bin")),
+            Self::Italian => Trie { data: include_bytes!("../tries/it.bin") },
             #[cfg(feature = "kurmanji")]
-            Self::Kurmanji => State::root(include_bytes!("../tries/ku.bin")),
+            Self::Kurmanji => Trie { data: include_bytes!("../tries/ku.bin") },
             #[cfg(feature = "latin")]
-            Self::Latin => State::root(include_bytes!("../tries/la.bin")),
+            Self::Latin => Trie { data: include_bytes!("../tries/la.bin") },
             #[cfg(feature = "lithuanian")]
-            Self::Lithuanian => State::root(include_bytes!("../tries/lt.bin")),
+            Self::Lithuanian => Trie { data: include_bytes!("../tries/lt.bin") },
             #[cfg(feature = "mongolian")]
-            Self::Mongolian => State::root(include_bytes!("../tries/mn.bin")),
+            Self::Mongolian => Trie { data: include_bytes!("../tries/mn.bin") },
             #[cfg(feature = "norwegian")]
-            Self::Norwegian => State::root(include_bytes!("../tries/no.bin")),
+            Self::Norwegian => Trie { data: include_bytes!("../tries/no.bin") },
             #[cfg(feature = "polish")]
-            Self::Polish => State::root(include_bytes!("../tries/pl.bin")),
+            Self::Polish => Trie { data: include_bytes!("../tries/pl.bin") },
             #[cfg(feature = "portuguese")]
-            Self::Portuguese => State::root(include_bytes!("../tries/pt.bin")),
+            Self::Portuguese => Trie { data: include_bytes!("../tries/pt.bin") },
             #[cfg(feature = "russian")]
-            Self::Russian => State::root(include_bytes!("../tries/ru.bin")),
+            Self::Russian => Trie { data: include_bytes!("../tries/ru.bin") },
             #[cfg(feature = "serbian")]
-            Self::Serbian => State::root(include_bytes!("../tries/sr.bin")),
+            Self::Serbian => Trie { data: include_bytes!("../tries/sr.bin") },
             #[cfg(feature = "slovak")]
-            Self::Slovak => State::root(include_bytes!("../tries/sk.bin")),
+            Self::Slovak => Trie { data: include_bytes!("../tries/sk.bin") },
             #[cfg(feature = "slovenian")]
-            Self::Slovenian => State::root(include_bytes!("../tries/sl.bin")),
+            Self::Slovenian => Trie { data: include_bytes!("../tries/sl.bin") },
             #[cfg(feature = "spanish")]
-            Self::Spanish => State::root(include_bytes!("../tries/es.bin")),
+            Self::Spanish => Trie { data: include_bytes!("../tries/es.bin") },
             #[cfg(feature = "swedish")]
-            Self::Swedish => State::root(include_bytes!("../tries/sv.bin")),
+            Self::Swedish => Trie { data: include_bytes!("../tries/sv.bin") },
             #[cfg(feature = "turkish")]
-            Self::Turkish => State::root(include_bytes!("../tries/tr.bin")),
+            Self::Turkish => Trie { data: include_bytes!("../tries/tr.bin") },
             #[cfg(feature = "turkmen")]
-            Self::Turkmen => State::root(include_bytes!("../tries/tk.bin")),
+            Self::Turkmen => Trie { data: include_bytes!("../tries/tk.bin") },
             #[cfg(feature = "ukrainian")]
-            Self::Ukrainian => State::root(include_bytes!("../tries/uk.bin")),
+            Self::Ukrainian => Trie { data: include_bytes!("../tries/uk.bin") },
             #[cfg(feature = "dyn")]
-            Self::Dyn { bytes, .. } => State::root(bytes),
+            Self::Dyn(trie) => trie,
             #[cfg(not(feature = "dyn"))]
             Self::Absurd(_) => unreachable!(),
         }
@@ -9,7 +9,7 @@ _hypher_ separates words into syllables.
 - No allocations unless when hyphenating very long words (> 45 bytes). You can
   disable the `alloc` feature, but then overly long words lead to a panic.
 - Support for many languages.
-- No unsafe code, no dependencies, no std.
+- No unsafe code (except for the optional C bindings), no dependencies, no std.
 
 # Example
 */
@@ -49,16 +49,22 @@ which ones get added:
 [dependencies]
 hypher = { version = "0.1", default-features = false, features = ["english", "greek"] }
 ```
+
+With the `dyn` feature, you can also load tries compiled by the `build`
+feature's `builder` module at runtime through `Lang::from_bytes`. Like
+hyphenation itself, this works without `std`. Only the builder and the
+command line tool need `std`.
 */
 
 #![cfg_attr(not(any(feature = "build", test)), no_std)]
-#![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "ffi"), forbid(unsafe_code))]
+#![cfg_attr(feature = "ffi", deny(unsafe_code))]
 #![deny(missing_docs)]
 
-#[cfg(any(feature = "alloc", test))]
+#[cfg(any(feature = "alloc", feature = "build", test))]
 extern crate alloc;
 
-use core::fmt::{self, Debug, Formatter};
+use core::fmt::{self, Debug, Display, Formatter};
 use core::iter::FusedIterator;
 use core::num::NonZeroU8;
 
@@ -66,6 +72,12 @@ use core::num::NonZeroU8;
 #[cfg(any(feature = "build", test))]
 pub mod builder;
 
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
 // Include language data.
 include!("lang.rs");
 
@@ -73,7 +85,28 @@ include!("lang.rs");
 ///
 /// Returns an iterator over the syllables.
 ///
-/// This uses the default [bounds](Lang::bounds) for the language.
+/// This uses the default [bounds](Lang::bounds) for the language. The patterns
+/// are matched case-insensitively, but the syllables are slices of the
+/// original word and thus keep its casing.
+///
+/// Separators like hyphens, commas, brackets and quotation marks split the
+/// word into runs that are hyphenated as words of their own, so there is never
+/// a break next to a separator: `"well-being"` is hyphenated as
+/// `well-be-ing` and `"hello,"` just like `hello`. In particular, a break at
+/// an existing hyphen is left to the caller, who can always break there, and
+/// joining the syllables with a hyphen never results in a double hyphen. See
+/// [`is_letter`] for which chars are separators and [`hyphenate_classified`]
+/// to change that.
+///
+/// Apostrophes are not separators, but left to the patterns of the language.
+/// A typographic apostrophe (U+2019) is matched as if it were an ASCII one.
+/// Soft hyphens (U+00AD) are not treated specially, use [`hyphenate_hinted`]
+/// to break at them.
+///
+/// A word with fewer chars than the [bounds](Lang::bounds) add up to, like a
+/// single letter, is never broken and yields itself as its only syllable. The
+/// empty word yields no syllables at all, so that joining them always gives
+/// back the word. Use [`hyphenate_bounded`] to override the bounds.
 ///
 /// # Panics
 /// Panics if the word is more than [`MAX_INLINE_SIZE`] bytes long and the `alloc`
@@ -97,7 +130,10 @@ pub fn hyphenate<'a>(word: &'a str, lang: Lang<'a>) -> Syllables<'a> {
 /// Segment a word into syllables, but forbid breaking between the given number
 /// of chars to each side.
 ///
-/// Returns an iterator over the syllables.
+/// Returns an iterator over the syllables. The bounds replace the language's
+/// default [bounds](Lang::bounds) and apply to each run of letters between
+/// separators (see [`hyphenate`]). If they add up to more than the number of
+/// chars in a run, the run is not broken at all.
 ///
 /// # Panics
 /// Panics if the word is more than [`MAX_INLINE_SIZE`] bytes long and the `alloc`
@@ -119,171 +155,1499 @@ pub fn hyphenate_bounded<'a>(
     left_min: usize,
     right_min: usize,
 ) -> Syllables<'a> {
-    // Initialize the trie state for the language.
-    let root = lang.root();
-
-    // Lowercase and add dots before and after the word..
-    let dotted = lowercase_and_dot(word);
-    let dotted = dotted.as_slice();
-
-    // Convert char bounds to byte bounds in the dotted word.
-    let (min_idx, max_idx) = char_to_byte_bounds(word, left_min, right_min);
+    lang.trie().hyphenate_bounded(word, left_min, right_min)
+}
 
-    // The levels between each two inner bytes of the word.
-    let mut levels = Bytes::zeros(word.len().saturating_sub(1));
-    let levels_mut = levels.as_mut_slice();
+/// Segment a word into syllables, with a custom notion of which chars are
+/// letters.
+///
+/// This is like [`hyphenate`], but `is_letter` decides which chars belong to
+/// the runs of letters that are hyphenated independently instead of
+/// [`is_letter`]. All other chars are separators, next to which there is
+/// never a break. This lets domain-specific tokens like chemical names or
+/// code identifiers be split differently.
+///
+/// # Panics
+/// Panics if the word is more than [`MAX_INLINE_SIZE`] bytes long and the `alloc`
+/// feature is disabled.
+///
+/// # Example
+/// Treating underscores as letters to hyphenate identifiers as a whole.
+/// ```
+/// # use hypher::{hyphenate, hyphenate_classified, is_letter, Lang};
+/// let syllables = hyphenate("my_variable_name", Lang::English);
+/// assert_eq!(syllables.join("-"), "my_vari-able_name");
+///
+/// let is_ident = |c| c == '_' || is_letter(c);
+/// let syllables = hyphenate_classified("my_variable_name", Lang::English, is_ident);
+/// assert_eq!(syllables.join("-"), "my_-vari-able_-name");
+/// ```
+pub fn hyphenate_classified<'a, F>(
+    word: &'a str,
+    lang: Lang<'a>,
+    is_letter: F,
+) -> Syllables<'a>
+where
+    F: Fn(char) -> bool,
+{
+    let (left_min, right_min) = lang.bounds();
+    hyphenate_from(word, lang.trie().root(), left_min, right_min, is_letter)
+}
 
-    // Start pattern matching at each character boundary.
-    for start in 0..dotted.len() {
-        if !is_char_boundary(dotted[start]) {
-            continue;
-        }
+/// Segment a word into syllables, but only if it has at least `min_len` chars.
+///
+/// Shorter words are returned as a single syllable. Unlike the
+/// [bounds](Lang::bounds), which forbid breaks close to the start and end of
+/// each run of letters, this decides for the whole word, counting all of its
+/// chars including separators. Longer words are hyphenated like with
+/// [`hyphenate`].
+///
+/// # Panics
+/// Panics if the word is more than [`MAX_INLINE_SIZE`] bytes long and the `alloc`
+/// feature is disabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_min_len, Lang};
+/// let mut syllables = hyphenate_min_len("shiny", Lang::English, 6);
+/// assert_eq!(syllables.next(), Some("shiny"));
+/// assert_eq!(syllables.next(), None);
+///
+/// let mut syllables = hyphenate_min_len("extensive", Lang::English, 6);
+/// assert_eq!(syllables.next(), Some("ex"));
+/// ```
+pub fn hyphenate_min_len<'a>(
+    word: &'a str,
+    lang: Lang<'a>,
+    min_len: usize,
+) -> Syllables<'a> {
+    if word.chars().nth(min_len.saturating_sub(1)).is_none() {
+        let levels = Bytes::zeros(word.len().saturating_sub(1));
+        return Syllables { word, cursor: 0, levels };
+    }
+    hyphenate(word, lang)
+}
 
-        let mut state = root;
-        for &b in &dotted[start..] {
-            if let Some(next) = state.transition(b) {
-                state = next;
-                for (offset, level) in state.levels() {
-                    let split = start + offset;
-
-                    // Example
-                    //
-                    // Dotted: . h e l l o .
-                    // Levels:    0 2 3 0
-                    if split >= min_idx && split <= max_idx {
-                        let slot = &mut levels_mut[split - 2];
-                        *slot = (*slot).max(level);
-                    }
-                }
-            } else {
-                break;
-            }
+/// Segment a word into syllables with the first of several languages that
+/// can break it.
+///
+/// Tries the languages in order, each with its default [bounds](Lang::bounds),
+/// and returns the syllables of the first one that finds at least one break.
+/// This helps when the language of a word is uncertain. If none of them
+/// breaks the word, it is returned as a single syllable, just like with
+/// [`hyphenate`]. Like `hyphenate`, this doesn't allocate for short words.
+///
+/// # Panics
+/// Panics if the word is more than [`MAX_INLINE_SIZE`] bytes long and the `alloc`
+/// feature is disabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_fallback, Lang};
+/// # #[cfg(all(feature = "english", feature = "french"))] {
+/// let mut syllables = hyphenate_fallback("playing", &[Lang::French, Lang::English]);
+/// assert_eq!(syllables.next(), Some("play"));
+/// assert_eq!(syllables.next(), Some("ing"));
+/// assert_eq!(syllables.next(), None);
+/// # }
+/// ```
+pub fn hyphenate_fallback<'a>(word: &'a str, langs: &[Lang<'a>]) -> Syllables<'a> {
+    for &lang in langs {
+        let syllables = hyphenate(word, lang);
+        if syllables.len() > 1 {
+            return syllables;
         }
     }
-
-    // Break into segments at odd levels.
+    let levels = Bytes::zeros(word.len().saturating_sub(1));
     Syllables { word, cursor: 0, levels }
 }
 
-/// Lowercase a word and add dots before and after it.
+/// Find the byte offsets in a word at which it may be hyphenated.
 ///
-/// The dots enable patterns that match based on whether they are at the edges
-/// of the word.
-fn lowercase_and_dot(word: &str) -> Bytes {
-    let mut dotted = Bytes::zeros(word.len() + 2);
-    let dotted_mut = dotted.as_mut_slice();
-    dotted_mut[0] = b'.';
+/// This uses the default [bounds](Lang::bounds) for the language. All offsets
+/// are char boundaries of the word and a break at offset `i` means that the
+/// hyphen goes between `word[..i]` and `word[i..]`.
+///
+/// # Panics
+/// Panics if the word is more than [`MAX_INLINE_SIZE`] bytes long and the `alloc`
+/// feature is disabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_positions, Lang};
+/// let mut positions = hyphenate_positions("extensive", Lang::English);
+/// assert_eq!(positions.next(), Some(2));
+/// assert_eq!(positions.next(), Some(5));
+/// assert_eq!(positions.next(), None);
+/// ```
+pub fn hyphenate_positions<'a>(word: &'a str, lang: Lang<'a>) -> Positions {
+    hyphenate(word, lang).into_positions()
+}
 
-    // Add the lowercased chars.
-    let mut offset = 1;
-    for mut c in word.chars() {
-        let mut lower = c.to_lowercase();
-        if let (Some(l), None) = (lower.next(), lower.next()) {
-            if l.len_utf8() == c.len_utf8() {
-                c = l;
-            }
+/// Find the char indices in a word at which it may be hyphenated.
+///
+/// This is like [`hyphenate_positions`], but counts chars instead of bytes: A
+/// break at index `i` means that the hyphen goes after the first `i` chars of
+/// the word. For ASCII words, both are the same.
+///
+/// # Panics
+/// Panics if the word is more than [`MAX_INLINE_SIZE`] bytes long and the `alloc`
+/// feature is disabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_char_positions, hyphenate_positions, Lang};
+/// # #[cfg(feature = "german")] {
+/// let positions = hyphenate_positions("häßlicher", Lang::German);
+/// assert_eq!(positions.collect::<Vec<_>>(), [5, 7]);
+///
+/// let positions = hyphenate_char_positions("häßlicher", Lang::German);
+/// assert_eq!(positions.collect::<Vec<_>>(), [3, 5]);
+/// # }
+/// ```
+pub fn hyphenate_char_positions<'a>(word: &'a str, lang: Lang<'a>) -> CharPositions<'a> {
+    let positions = hyphenate_positions(word, lang);
+    CharPositions { word, offset: 0, index: 0, positions }
+}
+
+/// Find the byte ranges of the syllables of a word.
+///
+/// This uses the default [bounds](Lang::bounds) for the language. The ranges
+/// are consecutive and together cover the whole word, so that `word[range]`
+/// gives the same syllables as [`hyphenate`]. The empty word has no ranges.
+///
+/// # Panics
+/// Panics if the word is more than [`MAX_INLINE_SIZE`] bytes long and the `alloc`
+/// feature is disabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_ranges, Lang};
+/// let mut ranges = hyphenate_ranges("extensive", Lang::English);
+/// assert_eq!(ranges.next(), Some(0..2));
+/// assert_eq!(ranges.next(), Some(2..5));
+/// assert_eq!(ranges.next(), Some(5..9));
+/// assert_eq!(ranges.next(), None);
+/// ```
+pub fn hyphenate_ranges<'a>(word: &'a str, lang: Lang<'a>) -> Ranges {
+    let positions = hyphenate_positions(word, lang);
+    Ranges { start: 0, end: word.len(), positions }
+}
+
+/// Find the byte ranges of the syllables of a word given as UTF-8 bytes.
+///
+/// This is like [`hyphenate_ranges`], but for callers that hold the word as a
+/// byte slice, like byte-oriented parsers, and don't want to convert it to a
+/// `str` first. For valid UTF-8, the ranges are the same as those of
+/// `hyphenate_ranges`. Invalid sequences are treated like separators (see
+/// [`hyphenate`]), so there is never a break inside of or next to them, and
+/// the valid parts are hyphenated as usual. Slicing the word with the ranges
+/// only gives valid UTF-8 if the word was valid, which the caller must ensure
+/// if they rely on it.
+///
+/// # Panics
+/// Panics if the word is more than [`MAX_INLINE_SIZE`] bytes long and the `alloc`
+/// feature is disabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_bytes, Lang};
+/// let mut ranges = hyphenate_bytes(b"extensive", Lang::English);
+/// assert_eq!(ranges.next(), Some(0..2));
+/// assert_eq!(ranges.next(), Some(2..5));
+/// assert_eq!(ranges.next(), Some(5..9));
+/// assert_eq!(ranges.next(), None);
+/// ```
+pub fn hyphenate_bytes<'a>(word: &'a [u8], lang: Lang<'a>) -> Ranges {
+    let (left_min, right_min) = lang.bounds();
+    let root = lang.trie().root();
+    let mut levels = Bytes::zeros(word.len().saturating_sub(1));
+    let levels_mut = levels.as_mut_slice();
+    for (start, valid) in utf8_chunks(word) {
+        for (offset, run) in runs(valid, is_letter) {
+            let levels = &mut levels_mut[start + offset..];
+            hyphenate_run(run, root, left_min, right_min, levels);
         }
-        offset += c.encode_utf8(&mut dotted_mut[offset..]).len();
     }
 
-    debug_assert_eq!(offset, word.len() + 1);
-    dotted_mut[offset] = b'.';
-    dotted
+    let positions = Positions { offset: 0, levels };
+    Ranges { start: 0, end: word.len(), positions }
 }
 
-/// Convert char bounds to byte bounds in the dotted word.
-fn char_to_byte_bounds(word: &str, left_min: usize, right_min: usize) -> (usize, usize) {
-    // It makes no sense to split outside the word.
-    let left_min = left_min.max(1);
-    let right_min = right_min.max(1);
-
-    // Convert from chars to byte indices in the dotted word.
-    let min_idx = 1 + word.chars().take(left_min).map(char::len_utf8).sum::<usize>();
-    let max_idx = 1 + word.len()
-        - word.chars().rev().take(right_min).map(char::len_utf8).sum::<usize>();
+/// Split bytes into the longest valid UTF-8 parts between invalid sequences,
+/// together with their byte offsets.
+fn utf8_chunks(bytes: &[u8]) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = 0;
+    core::iter::from_fn(move || {
+        while start < bytes.len() {
+            let rest = &bytes[start..];
+            let (valid, skip) = match core::str::from_utf8(rest) {
+                Ok(valid) => (valid, 0),
+                Err(err) => {
+                    let (valid, invalid) = rest.split_at(err.valid_up_to());
+                    let skip = err.error_len().unwrap_or(invalid.len());
+                    (core::str::from_utf8(valid).unwrap_or_default(), skip)
+                }
+            };
+            let chunk = (start, valid);
+            start += valid.len() + skip;
+            if !valid.is_empty() {
+                return Some(chunk);
+            }
+        }
+        None
+    })
+}
 
-    (min_idx, max_idx)
+/// Segment a word into syllables, each tagged with whether a break follows it.
+///
+/// This is like [`hyphenate`], but yields each syllable together with `true`
+/// if another syllable follows, so that a hyphen goes after it, and `false`
+/// for the last one.
+///
+/// # Panics
+/// Panics if the word is more than [`MAX_INLINE_SIZE`] bytes long and the `alloc`
+/// feature is disabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_tagged, Lang};
+/// let mut syllables = hyphenate_tagged("extensive", Lang::English);
+/// assert_eq!(syllables.next(), Some(("ex", true)));
+/// assert_eq!(syllables.next(), Some(("ten", true)));
+/// assert_eq!(syllables.next(), Some(("sive", false)));
+/// assert_eq!(syllables.next(), None);
+/// ```
+pub fn hyphenate_tagged<'a>(word: &'a str, lang: Lang<'a>) -> TaggedSyllables<'a> {
+    TaggedSyllables { syllables: hyphenate(word, lang) }
 }
 
-/// An iterator over the syllables of a word.
+/// Find the single break closest to the middle of a word, for example to
+/// split a label over two lines.
 ///
-/// This struct is created by [`hyphenate`] and [`hyphenate_bounded`].
-#[derive(Debug, Clone)]
-pub struct Syllables<'a> {
-    word: &'a str,
-    cursor: usize,
-    levels: Bytes,
+/// Returns the byte offset of the break like [`hyphenate_positions`], or
+/// `None` if the word can't be broken. The middle is measured in chars and
+/// of two breaks that are equally close, the earlier one is picked.
+///
+/// # Panics
+/// Panics if the word is more than [`MAX_INLINE_SIZE`] bytes long and the `alloc`
+/// feature is disabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_best_break, Lang};
+/// assert_eq!(hyphenate_best_break("Probability", Lang::English), Some(5));
+/// assert_eq!(hyphenate_best_break("hello", Lang::English), None);
+/// ```
+pub fn hyphenate_best_break(word: &str, lang: Lang) -> Option<usize> {
+    let count = word.chars().count();
+    let mut offset = 0;
+    let mut index = 0;
+    hyphenate_positions(word, lang).min_by_key(|&position| {
+        index += word[offset..position].chars().count();
+        offset = position;
+        (2 * index).abs_diff(count)
+    })
 }
 
-impl Syllables<'_> {
-    /// Join the syllables with a separator like a hyphen or soft hyphen.
-    ///
-    /// This is only available when the `alloc` feature is enabled.
-    ///
-    /// # Example
-    /// Adding soft hyphens at every opportunity.
-    /// ```
-    /// # use hypher::{hyphenate, Lang};
-    /// # let joined =
-    /// hyphenate("wonderful", Lang::English).join("\u{ad}");
-    /// # assert_eq!(joined, "won\u{ad}der\u{ad}ful")
-    /// ```
-    #[cfg(any(feature = "alloc", test))]
-    pub fn join(mut self, sep: &str) -> alloc::string::String {
-        let extra = self.splits() * sep.len();
-        let mut s = alloc::string::String::with_capacity(self.word.len() + extra);
-        s.extend(self.next());
-        for syllable in self {
-            s.push_str(sep);
-            s.push_str(syllable);
+/// Segment a word into syllables, but with at most `max` breaks.
+///
+/// This caps the hyphenation of long words, for example in narrow columns.
+/// Of the breaks found by [`hyphenate`], those closest to the middle of the
+/// word are kept, measured in chars like with [`hyphenate_best_break`]. Of
+/// two breaks that are equally close, the earlier one is preferred. With
+/// `max` set to zero, the word is never broken.
+///
+/// # Panics
+/// Panics if the word is more than [`MAX_INLINE_SIZE`] bytes long and the `alloc`
+/// feature is disabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_max_breaks, Lang};
+/// let mut syllables = hyphenate_max_breaks("extensive", Lang::English, 1);
+/// assert_eq!(syllables.next(), Some("exten"));
+/// assert_eq!(syllables.next(), Some("sive"));
+/// assert_eq!(syllables.next(), None);
+/// ```
+pub fn hyphenate_max_breaks<'a>(
+    word: &'a str,
+    lang: Lang<'a>,
+    max: usize,
+) -> Syllables<'a> {
+    let mut syllables = hyphenate(word, lang);
+    let breaks = syllables.levels.clone();
+    let breaks = breaks.as_slice();
+    let is_break =
+        |&(i, _): &(usize, usize)| i > 0 && i < word.len() && breaks[i - 1] % 2 == 1;
+
+    // Walk outwards from the middle char, visiting the breaks in the order of
+    // their distance to the middle.
+    let count = word.chars().count();
+    let half = count / 2;
+    let mid = word.char_indices().nth(half).map_or(word.len(), |(i, _)| i);
+    let before = word[..mid].char_indices().rev().map(|(i, _)| i);
+    let mut left = core::iter::once(mid)
+        .chain(before)
+        .zip((0..=half).rev())
+        .filter(is_break)
+        .map(|(i, j)| (count - 2 * j, i))
+        .peekable();
+    let after = word[mid..].char_indices().skip(1).map(|(i, _)| mid + i);
+    let mut right = after
+        .zip(half + 1..)
+        .filter(is_break)
+        .map(|(i, j)| (2 * j - count, i))
+        .peekable();
+
+    let levels = syllables.levels.as_mut_slice();
+    let mut kept = 0;
+    loop {
+        let take_left = match (left.peek(), right.peek()) {
+            (Some(l), Some(r)) => l.0 <= r.0,
+            (l, _) => l.is_some(),
+        };
+        let next = if take_left { left.next() } else { right.next() };
+        match next {
+            Some(_) if kept < max => kept += 1,
+            Some((_, i)) => levels[i - 1] = 0,
+            None => break,
         }
-        s
     }
 
-    /// The remaining number of splits in the word.
-    fn splits(&self) -> usize {
-        self.levels.as_slice().iter().filter(|&lvl| lvl % 2 == 1).count()
-    }
+    syllables
 }
 
-impl<'a> Iterator for Syllables<'a> {
-    type Item = &'a str;
+/// Segment a word that may contain soft hyphens (U+00AD) into syllables.
+///
+/// Soft hyphens are hints by the author of a text that mark where a word may
+/// be broken. Each of them becomes a break and is itself left out of the
+/// syllables, so that they never end up in the output. With [`Hints::Add`],
+/// the breaks found by the patterns are kept as well, and with
+/// [`Hints::Only`], the soft hyphens are the only breaks.
+///
+/// The patterns are matched against the word without its soft hyphens and
+/// the language's default [bounds](Lang::bounds) apply to that word. Soft
+/// hyphens always break, even within the bounds, as they are explicit.
+///
+/// # Panics
+/// Panics if the word is more than [`MAX_INLINE_SIZE`] bytes long and the `alloc`
+/// feature is disabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_hinted, Hints, Lang};
+/// let word = "hyphen\u{ad}ation";
+/// let syllables: Vec<_> = hyphenate_hinted(word, Lang::English, Hints::Add).collect();
+/// assert_eq!(syllables, ["hy", "phen", "ation"]);
+///
+/// let word = "ex\u{ad}tensive";
+/// let syllables: Vec<_> = hyphenate_hinted(word, Lang::English, Hints::Only).collect();
+/// assert_eq!(syllables, ["ex", "tensive"]);
+/// ```
+pub fn hyphenate_hinted<'a>(
+    word: &'a str,
+    lang: Lang<'a>,
+    hints: Hints,
+) -> HintedSyllables<'a> {
+    let mut levels = Bytes::zeros(word.len().saturating_sub(1));
+    let levels_mut = levels.as_mut_slice();
+    let mut set = |offset: usize, level: u8| {
+        if let Some(slot) = offset.checked_sub(1).and_then(|i| levels_mut.get_mut(i)) {
+            *slot = level;
+        }
+    };
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let found = self.levels.any(|lvl| lvl % 2 == 1);
-        let start = self.cursor;
-        let end = self.word.len() - self.levels.len() - found as usize;
-        self.cursor = end;
-        (start < end).then(|| &self.word[start..end])
+    // Hyphenate the word without its soft hyphens and transfer the levels.
+    if hints == Hints::Add {
+        let hints = word.matches(SOFT_HYPHEN).count();
+        let mut clean = Bytes::zeros(word.len() - hints * SOFT_HYPHEN.len_utf8());
+        let clean_mut = clean.as_mut_slice();
+        let mut offset = 0;
+        for c in word.chars().filter(|&c| c != SOFT_HYPHEN) {
+            offset += c.encode_utf8(&mut clean_mut[offset..]).len();
+        }
+
+        let clean = core::str::from_utf8(clean.as_slice()).unwrap();
+        let clean_levels = hyphenate(clean, lang).levels;
+        let clean_levels = clean_levels.as_slice();
+        let mut clean_offset: usize = 0;
+        for (offset, c) in word.char_indices().filter(|&(_, c)| c != SOFT_HYPHEN) {
+            let level = clean_offset.checked_sub(1).and_then(|i| clean_levels.get(i));
+            set(offset, level.copied().unwrap_or(0));
+            clean_offset += c.len_utf8();
+        }
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let len = if self.word.is_empty() { 0 } else { 1 + self.splits() };
-        (len, Some(len))
+    // Break before and after each soft hyphen, so that it becomes a syllable
+    // of its own that is then skipped.
+    for (offset, _) in word.match_indices(SOFT_HYPHEN) {
+        set(offset, 1);
+        set(offset + SOFT_HYPHEN.len_utf8(), 1);
     }
+
+    HintedSyllables { syllables: Syllables { word, cursor: 0, levels } }
 }
 
-impl ExactSizeIterator for Syllables<'_> {}
+/// The soft hyphen (U+00AD), which marks a possible break in a text.
+const SOFT_HYPHEN: char = '\u{ad}';
 
-impl FusedIterator for Syllables<'_> {}
+/// How [`hyphenate_hinted`] treats the soft hyphens in a word.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Hints {
+    /// Break at the soft hyphens in addition to where the patterns permit it.
+    Add,
+    /// Break only at the soft hyphens.
+    Only,
+}
 
-/// The maximum size (in bytes) of words that may be hyphenated without
-/// allocating.
-pub const MAX_INLINE_SIZE: usize = 45;
-const INLINE_BUF_SIZE: usize = MAX_INLINE_SIZE + 2; // +2 for dots
+/// Insert a separator at every position where a word may be hyphenated.
+///
+/// This uses the default [bounds](Lang::bounds) for the language and gives
+/// the same string as joining the syllables from [`hyphenate`] with `sep`,
+/// ready to be displayed.
+///
+/// This is only available when the `alloc` feature is enabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_string, Lang};
+/// let hyphenated = hyphenate_string("wonderful", Lang::English, '·');
+/// assert_eq!(hyphenated, "won·der·ful");
+/// ```
+#[cfg(any(feature = "alloc", test))]
+pub fn hyphenate_string(word: &str, lang: Lang, sep: char) -> alloc::string::String {
+    join_at(word, hyphenate_positions(word, lang), sep.encode_utf8(&mut [0; 4]))
+}
 
-/// Storage for and iterator over bytes.
-#[derive(Clone)]
-enum Bytes {
-    Array([u8; INLINE_BUF_SIZE], NonZeroU8),
-    #[cfg(feature = "alloc")]
-    Vec(alloc::vec::IntoIter<u8>),
+/// Insert a soft hyphen (U+00AD) at every position where a word may be
+/// hyphenated.
+///
+/// This uses the default [bounds](Lang::bounds) for the language. The chars of
+/// the word are left untouched, so that a renderer can break the word at the
+/// soft hyphens if needed.
+///
+/// This is only available when the `alloc` feature is enabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_soft, Lang};
+/// let soft = hyphenate_soft("wonderful", Lang::English);
+/// assert_eq!(soft, "won\u{ad}der\u{ad}ful");
+/// ```
+#[cfg(any(feature = "alloc", test))]
+pub fn hyphenate_soft(word: &str, lang: Lang) -> alloc::string::String {
+    hyphenate_string(word, lang, SOFT_HYPHEN)
 }
 
-impl Bytes {
-    /// Create zero-initialized bytes.
-    fn zeros(len: usize) -> Self {
-        if len <= INLINE_BUF_SIZE {
+/// Join the syllables of a word with a separator, but only allocate if there
+/// is more than one.
+///
+/// This uses the default [bounds](Lang::bounds) for the language. Words that
+/// can't be hyphenated, which includes most short words, are returned as they
+/// are.
+///
+/// This is only available when the `alloc` feature is enabled.
+///
+/// # Example
+/// ```
+/// # use std::borrow::Cow;
+/// # use hypher::{hyphenate_cow, Lang};
+/// assert_eq!(hyphenate_cow("the", Lang::English, "-"), Cow::Borrowed("the"));
+/// assert_eq!(hyphenate_cow("extensive", Lang::English, "-"), "ex-ten-sive");
+/// ```
+#[cfg(any(feature = "alloc", test))]
+pub fn hyphenate_cow<'a>(
+    word: &'a str,
+    lang: Lang,
+    sep: &str,
+) -> alloc::borrow::Cow<'a, str> {
+    let positions = hyphenate_positions(word, lang);
+    if positions.len() == 0 {
+        alloc::borrow::Cow::Borrowed(word)
+    } else {
+        alloc::borrow::Cow::Owned(join_at(word, positions, sep))
+    }
+}
+
+/// Insert a separator at the given byte positions of a word.
+#[cfg(any(feature = "alloc", test))]
+fn join_at(word: &str, positions: Positions, sep: &str) -> alloc::string::String {
+    let extra = positions.len() * sep.len();
+    let mut s = alloc::string::String::with_capacity(word.len() + extra);
+    let mut start = 0;
+    for end in positions {
+        s.push_str(&word[start..end]);
+        s.push_str(sep);
+        start = end;
+    }
+    s.push_str(&word[start..]);
+    s
+}
+
+/// Segment a word into syllables, writing them into a reusable buffer.
+///
+/// The buffer is cleared first and then filled with the syllables, which
+/// borrow from `word`. Like [`hyphenate`], this doesn't allocate by itself for
+/// words of up to [`MAX_INLINE_SIZE`] bytes, so reusing one buffer across many
+/// words avoids allocating per word once the buffer has grown large enough.
+///
+/// This is only available when the `alloc` feature is enabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_into, Lang};
+/// let mut buf = Vec::new();
+/// for word in ["extensive", "wonderful"] {
+///     hyphenate_into(word, Lang::English, &mut buf);
+///     assert_eq!(buf.len(), 3);
+/// }
+/// assert_eq!(buf, ["won", "der", "ful"]);
+/// ```
+#[cfg(any(feature = "alloc", test))]
+pub fn hyphenate_into<'a>(
+    word: &'a str,
+    lang: Lang<'a>,
+    out: &mut alloc::vec::Vec<&'a str>,
+) {
+    out.clear();
+    out.extend(hyphenate(word, lang));
+}
+
+/// Compute the level at each byte offset of a word, for debugging patterns.
+///
+/// Returns `word.len() + 1` levels, where the level at offset `i` is the
+/// maximum level that the patterns (or an exception) put between `word[..i]`
+/// and `word[i..]`. Odd levels permit a break, but this is before the
+/// language's [bounds](Lang::bounds) are applied, so even the first and last
+/// offsets get a level. Offsets inside of multi-byte chars and next to
+/// separators (see [`hyphenate`]) that don't border a run of letters always
+/// have level zero.
+///
+/// This is only available when the `alloc` feature is enabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_explain, Lang};
+/// let levels = hyphenate_explain("extensive", Lang::English);
+/// assert_eq!(levels.len(), 10);
+/// assert_eq!(levels[2] % 2, 1); // ex-tensive
+/// assert_eq!(levels[3] % 2, 0); // ext-ensive
+/// ```
+#[cfg(any(feature = "alloc", test))]
+pub fn hyphenate_explain(word: &str, lang: Lang) -> alloc::vec::Vec<u8> {
+    explain_from(word, lang.trie().root())
+}
+
+/// Compute the level at each byte offset of a word with the trie starting at
+/// `root`.
+#[cfg(any(feature = "alloc", test))]
+fn explain_from(word: &str, root: State) -> alloc::vec::Vec<u8> {
+    let mut levels = alloc::vec![0; word.len() + 1];
+    for (start, run) in runs(word, is_letter) {
+        let dotted_levels = run_levels(run, root);
+        let dotted_levels = dotted_levels.as_slice();
+        let mut pos = 1;
+        for (offset, c) in run.char_indices() {
+            levels[start + offset] = dotted_levels[pos];
+            pos += normalize(c).len_utf8();
+        }
+        levels[start + run.len()] = dotted_levels[pos];
+    }
+    levels
+}
+
+/// List the patterns that match a word, for debugging patterns.
+///
+/// Returns each pattern that matches somewhere in the word once, with its
+/// levels written as digits like in a pattern file, in the order in which
+/// they are found: by where they start in the word and then by length.
+/// Patterns are matched case-insensitively and against each run of letters
+/// between separators (see [`hyphenate`]) with dots before and after it. If
+/// a run has an exception, the exception is listed instead, with hyphens at
+/// its breaks, as the patterns are not used for it.
+///
+/// This is only available when the `alloc` feature is enabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{matching_patterns, Lang};
+/// let patterns = matching_patterns("hyphenation", Lang::English);
+/// assert!(patterns.contains(&"hy3ph".into()));
+/// ```
+#[cfg(any(feature = "alloc", test))]
+pub fn matching_patterns(
+    word: &str,
+    lang: Lang,
+) -> alloc::vec::Vec<alloc::string::String> {
+    patterns_from(word, lang.trie().root())
+}
+
+/// List the patterns of the trie starting at `root` that match a word.
+#[cfg(any(feature = "alloc", test))]
+fn patterns_from(word: &str, root: State) -> alloc::vec::Vec<alloc::string::String> {
+    let mut patterns = alloc::vec::Vec::new();
+    let mut push = |pattern| {
+        if !patterns.contains(&pattern) {
+            patterns.push(pattern);
+        }
+    };
+
+    for (_, run) in runs(word, is_letter) {
+        let dotted = lowercase_and_dot(run);
+        let dotted = dotted.as_slice();
+        if let Some(state) = exception(root, dotted) {
+            let mut path = alloc::vec![EXCEPTION];
+            path.extend_from_slice(&dotted[1..]);
+            push(decompile(&path, state.levels(), true));
+            continue;
+        }
+
+        for start in 0..dotted.len() {
+            if !is_char_boundary(dotted[start]) {
+                continue;
+            }
+
+            let mut state = root;
+            let mut end = start;
+            while let Some((next, len)) = state.step(&dotted[end..]) {
+                state = next;
+                end += len;
+                if state.is_final() {
+                    push(decompile(&dotted[start..end], state.levels(), false));
+                }
+            }
+        }
+    }
+
+    patterns
+}
+
+/// Turn the path to a final state and its levels, given as offsets into the
+/// path, back into a pattern or exception.
+///
+/// For patterns, the levels are written as digits. For exceptions, the
+/// leading [`EXCEPTION`] byte and the trailing dot are stripped and each level
+/// becomes a hyphen.
+#[cfg(any(feature = "alloc", feature = "build", test))]
+fn decompile<I>(path: &[u8], levels: I, exception: bool) -> alloc::string::String
+where
+    I: Iterator<Item = (usize, u8)>,
+{
+    let (letters, shift) = match path {
+        [EXCEPTION, inner @ .., b'.'] if exception => (inner, 1),
+        _ => (path, 0),
+    };
+
+    let mut levels = levels.peekable();
+    let mut bytes = alloc::vec![];
+    for i in 0..=letters.len() {
+        if let Some((_, level)) = levels.next_if(|&(offset, _)| offset == i + shift) {
+            bytes.push(if exception { b'-' } else { b'0' + level });
+        }
+        bytes.extend(letters.get(i));
+    }
+
+    alloc::string::String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Words with fixed breaks that take precedence over the patterns of a
+/// language.
+///
+/// This lets an application fix the hyphenation of a handful of words at
+/// runtime without rebuilding the trie, like a `\hyphenation` block in a
+/// pattern file. Words are matched case-insensitively and as a whole,
+/// separators included.
+///
+/// This is only available when the `alloc` feature is enabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{Exceptions, Lang};
+/// let mut exceptions = Exceptions::new();
+/// exceptions.insert("extensive", &[3]);
+/// let syllables = exceptions.hyphenate("Extensive", Lang::English);
+/// assert_eq!(syllables.collect::<Vec<_>>(), ["Ext", "ensive"]);
+///
+/// let syllables = exceptions.hyphenate("wonderful", Lang::English);
+/// assert_eq!(syllables.collect::<Vec<_>>(), ["won", "der", "ful"]);
+/// ```
+#[cfg(any(feature = "alloc", test))]
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Exceptions {
+    words: alloc::collections::BTreeMap<alloc::string::String, alloc::vec::Vec<usize>>,
+}
+
+#[cfg(any(feature = "alloc", test))]
+impl Exceptions {
+    /// Create an empty set of exceptions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fix the breaks of a word to the given byte offsets, replacing the
+    /// breaks of an earlier exception for the same word.
+    ///
+    /// Words are matched case-insensitively and typographic apostrophes match
+    /// ASCII ones, like with the patterns. Offsets at the start or end of the word or inside
+    /// of a multi-byte char are ignored. Without any offsets, the word is
+    /// never broken.
+    pub fn insert(&mut self, word: &str, positions: &[usize]) {
+        // Store the offsets into the normalized word, whose length can differ.
+        let mut normalized = alloc::string::String::with_capacity(word.len());
+        let mut offsets = alloc::vec::Vec::new();
+        for (offset, c) in word.char_indices() {
+            if offset > 0 && positions.contains(&offset) {
+                offsets.push(normalized.len());
+            }
+            normalized.push(normalize(c));
+        }
+        self.words.insert(normalized, offsets);
+    }
+
+    /// Remove the exception for a word, returning its breaks as byte offsets
+    /// into the given word.
+    pub fn remove(&mut self, word: &str) -> Option<alloc::vec::Vec<usize>> {
+        let offsets = self.words.remove(&normalized(word))?;
+        Some(denormalize(word, &offsets))
+    }
+
+    /// The breaks of a word as byte offsets into it if it is an exception.
+    pub fn get(&self, word: &str) -> Option<alloc::vec::Vec<usize>> {
+        let offsets = self.words.get(&normalized(word))?;
+        Some(denormalize(word, offsets))
+    }
+
+    /// The number of exceptions.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether there are no exceptions.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Segment a word into syllables at its fixed breaks if it is an
+    /// exception and like with [`hyphenate`] otherwise.
+    pub fn hyphenate<'a>(&self, word: &'a str, lang: Lang<'a>) -> Syllables<'a> {
+        let positions = match self.get(word) {
+            Some(positions) => positions,
+            None => return hyphenate(word, lang),
+        };
+
+        let mut levels = Bytes::zeros(word.len().saturating_sub(1));
+        let levels_mut = levels.as_mut_slice();
+        for i in positions {
+            levels_mut[i - 1] = 1;
+        }
+        Syllables { word, cursor: 0, levels }
+    }
+}
+
+/// Lowercase a word and normalize its apostrophes like [`lowercase_and_dot`].
+#[cfg(any(feature = "alloc", test))]
+fn normalized(word: &str) -> alloc::string::String {
+    word.chars().map(normalize).collect()
+}
+
+/// Map byte offsets into the [normalized] form of a word back to offsets into
+/// the word.
+#[cfg(any(feature = "alloc", test))]
+fn denormalize(word: &str, offsets: &[usize]) -> alloc::vec::Vec<usize> {
+    let mut pos = 0;
+    let mut result = alloc::vec::Vec::new();
+    for (offset, c) in word.char_indices() {
+        if offset > 0 && offsets.contains(&pos) {
+            result.push(offset);
+        }
+        pos += normalize(c).len_utf8();
+    }
+    result
+}
+
+/// Segment a word that mixes scripts into syllables, hyphenating the part in
+/// each script on its own.
+///
+/// This is like [`hyphenate`], but each run of letters is further split where
+/// the [script](Script::of) changes, so that there is never a break at a
+/// transition, like in a product name mixing Latin and Cyrillic letters. The
+/// parts written in the script of the language, or in no particular script,
+/// are hyphenated with the language. Other parts are hyphenated with the
+/// [language for their script](Lang::for_script) and its bounds, or not at
+/// all if there is none.
+///
+/// # Panics
+/// Panics if the word is more than [`MAX_INLINE_SIZE`] bytes long and the `alloc`
+/// feature is disabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate, hyphenate_mixed, Lang};
+/// # #[cfg(all(feature = "english", feature = "russian"))] {
+/// let syllables = hyphenate("superмаркет", Lang::Russian);
+/// assert_eq!(syllables.join("-"), "super-мар-кет");
+///
+/// let syllables = hyphenate_mixed("superмаркет", Lang::Russian);
+/// assert_eq!(syllables.join("-"), "su-perмар-кет");
+/// # }
+/// ```
+pub fn hyphenate_mixed<'a>(word: &'a str, lang: Lang<'a>) -> Syllables<'a> {
+    let mut levels = Bytes::zeros(word.len().saturating_sub(1));
+    let levels_mut = levels.as_mut_slice();
+    for (start, run) in runs(word, is_letter) {
+        for (offset, part, script) in script_runs(run) {
+            let lang = match (script, lang.script()) {
+                (Some(script), Some(own)) if script != own => {
+                    match Lang::for_script(script) {
+                        Some(other) => other,
+                        None => continue,
+                    }
+                }
+                _ => lang,
+            };
+            let (left_min, right_min) = lang.bounds();
+            let root = lang.trie().root();
+            hyphenate_run(
+                part,
+                root,
+                left_min,
+                right_min,
+                &mut levels_mut[start + offset..],
+            );
+        }
+    }
+    Syllables { word, cursor: 0, levels }
+}
+
+/// Segment many words into syllables in parallel.
+///
+/// Returns the syllables of each word, in the same order as the words. The
+/// syllables borrow from the words.
+///
+/// This is only available when the `rayon` feature is enabled.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate_all, Lang};
+/// let syllables = hyphenate_all(&["extensive", "wonderful"], Lang::English);
+/// assert_eq!(syllables, [["ex", "ten", "sive"], ["won", "der", "ful"]]);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn hyphenate_all<'a>(
+    words: &[&'a str],
+    lang: Lang<'a>,
+) -> alloc::vec::Vec<alloc::vec::Vec<&'a str>> {
+    use rayon::prelude::*;
+    words.par_iter().map(|word| hyphenate(word, lang).collect()).collect()
+}
+
+/// Extension trait to segment each token of an iterator into syllables.
+///
+/// This is implemented for all iterators over references to string-like
+/// tokens, like `Vec<&str>::iter()` or `Vec<String>::iter()`.
+///
+/// # Example
+/// ```
+/// # use hypher::{HyphenateExt, Lang};
+/// let tokens = vec!["extensive", "wonderful"];
+/// let mut hyphenated = tokens.iter().hyphenate(Lang::English);
+/// assert_eq!(hyphenated.next().unwrap().join("-"), "ex-ten-sive");
+/// assert_eq!(hyphenated.next().unwrap().join("-"), "won-der-ful");
+/// assert!(hyphenated.next().is_none());
+/// ```
+pub trait HyphenateExt<'a>: Iterator + Sized {
+    /// Lazily segment each token into syllables like with [`hyphenate`].
+    fn hyphenate(self, lang: Lang<'a>) -> HyphenateIter<'a, Self>;
+}
+
+impl<'a, I, T> HyphenateExt<'a> for I
+where
+    I: Iterator<Item = &'a T>,
+    T: AsRef<str> + ?Sized + 'a,
+{
+    fn hyphenate(self, lang: Lang<'a>) -> HyphenateIter<'a, Self> {
+        let (left_min, right_min) = lang.bounds();
+        HyphenateIter {
+            tokens: self,
+            root: lang.trie().root(),
+            left_min,
+            right_min,
+        }
+    }
+}
+
+/// Segment a word into syllables with the trie starting at `root`, splitting
+/// it into runs at the chars that aren't letters according to `is_letter`.
+fn hyphenate_from<'a, F>(
+    word: &'a str,
+    root: State,
+    left_min: usize,
+    right_min: usize,
+    is_letter: F,
+) -> Syllables<'a>
+where
+    F: Fn(char) -> bool,
+{
+    // The levels between each two inner bytes of the word.
+    let mut levels = Bytes::zeros(word.len().saturating_sub(1));
+    let levels_mut = levels.as_mut_slice();
+    for (start, run) in runs(word, is_letter) {
+        hyphenate_run(run, root, left_min, right_min, &mut levels_mut[start..]);
+    }
+
+    // Break into segments at odd levels.
+    Syllables { word, cursor: 0, levels }
+}
+
+/// Split a word into the runs between separators like hyphens or commas,
+/// together with their byte offsets.
+///
+/// The runs are hyphenated as words of their own, so that no break ends up
+/// next to a separator.
+fn runs<F>(word: &str, is_letter: F) -> impl Iterator<Item = (usize, &str)>
+where
+    F: Fn(char) -> bool,
+{
+    let mut start = 0;
+    let separators = word
+        .match_indices(move |c| !is_letter(c))
+        .map(|(i, sep)| (i, i + sep.len()));
+    separators
+        .chain(core::iter::once((word.len(), word.len())))
+        .filter_map(move |(end, next)| {
+            let run = (start < end).then(|| (start, &word[start..end]));
+            start = next;
+            run
+        })
+}
+
+/// Split a run of letters into the parts written in a single script, together
+/// with their byte offsets and scripts.
+///
+/// Chars without a script, like digits or apostrophes, belong to the part
+/// before them, or to the first part if they lead the run. A part without any
+/// chars of a known script has no script.
+fn script_runs(run: &str) -> impl Iterator<Item = (usize, &str, Option<Script>)> {
+    let mut start = 0;
+    core::iter::from_fn(move || {
+        let rest = run.get(start..).filter(|rest| !rest.is_empty())?;
+        let mut script = None;
+        let mut end = rest.len();
+        for (i, c) in rest.char_indices() {
+            match (script, Script::of(c)) {
+                (Some(a), Some(b)) if a != b => {
+                    end = i;
+                    break;
+                }
+                (None, Some(b)) => script = Some(b),
+                _ => {}
+            }
+        }
+        let part = (start, &rest[..end], script);
+        start += end;
+        Some(part)
+    })
+}
+
+/// Compute the levels between the bytes of a run of letters, forbidding
+/// breaks between the given number of chars to each side.
+///
+/// The level between `run[..i]` and `run[i..]` is written to `levels[i - 1]`.
+fn hyphenate_run(
+    run: &str,
+    root: State,
+    left_min: usize,
+    right_min: usize,
+    levels: &mut [u8],
+) {
+    // A run too short to be broken within the bounds needs no pattern
+    // matching at all. This also covers runs of a single char.
+    let count = run.chars().count();
+    let left_min = left_min.max(1);
+    let right_min = right_min.max(1);
+    if count < left_min.saturating_add(right_min) {
+        return;
+    }
+
+    let dotted_levels = run_levels(run, root);
+    let dotted_levels = dotted_levels.as_slice();
+
+    // Copy the levels within the bounds over to the run. It makes no sense
+    // to split outside the run. The dotted run can be shorter than the run
+    // because of normalized apostrophes, so we walk both in lockstep. Since
+    // only levels before chars are copied, a break never ends up inside of a
+    // multi-byte char, even if a malformed trie puts a level there.
+    let mut pos = 1;
+    for (i, (offset, c)) in run.char_indices().enumerate() {
+        if i >= left_min && count - i >= right_min {
+            levels[offset - 1] = dotted_levels[pos];
+        }
+        pos += normalize(c).len_utf8();
+    }
+}
+
+/// Compute the levels before each byte of a run of letters with dots before
+/// and after it.
+fn run_levels(run: &str, root: State) -> Bytes {
+    // Lowercase and add dots before and after the run.
+    let dotted = lowercase_and_dot(run);
+    let dotted = dotted.as_slice();
+
+    // The levels before each byte of the dotted run.
+    let mut levels = Bytes::zeros(dotted.len());
+    let dotted_levels = levels.as_mut_slice();
+    let mut record = |split: usize, level: u8| {
+        // Example
+        //
+        // Dotted: . h e l l o .
+        // Levels:  0 0 2 3 0 0
+        if let Some(slot) = dotted_levels.get_mut(split) {
+            *slot = (*slot).max(level);
+        }
+    };
+
+    if let Some(state) = exception(root, dotted) {
+        // An exception for the whole run takes precedence over the patterns.
+        for (offset, level) in state.levels() {
+            record(offset, level);
+        }
+    } else {
+        // Start pattern matching at each character boundary.
+        for start in 0..dotted.len() {
+            if !is_char_boundary(dotted[start]) {
+                continue;
+            }
+
+            let mut state = root;
+            let mut end = start;
+            while let Some((next, len)) = state.step(&dotted[end..]) {
+                state = next;
+                end += len;
+                for (offset, level) in state.levels() {
+                    record(start + offset, level);
+                }
+            }
+        }
+    }
+
+    levels
+}
+
+/// Whether a char is a letter, as opposed to a separator like a hyphen,
+/// comma, bracket or quotation mark.
+///
+/// This is how [`hyphenate`] splits a word into runs of letters that are
+/// hyphenated independently. Unicode alphabetic chars are letters and so are
+/// combining diacritical marks, which belong to the letter before them.
+/// Apostrophes (including the Greek koronis and psili) are letters as well,
+/// as they are part of words in many languages and the patterns know how to
+/// deal with them. Everything else, including digits and symbols, is a
+/// separator.
+///
+/// # Example
+/// ```
+/// # use hypher::is_letter;
+/// assert!(is_letter('é') && is_letter('\'') && is_letter('\u{301}'));
+/// assert!(!is_letter('-') && !is_letter('_') && !is_letter('«'));
+/// assert!(!is_letter('5') && !is_letter('€'));
+/// ```
+pub fn is_letter(c: char) -> bool {
+    match c {
+        '\'' | '’' | '\u{1FBD}' | '\u{1FBF}' | '\u{300}'..='\u{36F}' => true,
+        _ => c.is_alphabetic(),
+    }
+}
+
+/// Look up the exception for a whole dotted word, if there is one.
+///
+/// Exceptions are stored like patterns, but below the [`EXCEPTION`] transition
+/// of the root, which takes the place of the leading dot.
+fn exception<'a>(root: State<'a>, dotted: &[u8]) -> Option<State<'a>> {
+    let (mut state, _) = root.step(&[EXCEPTION])?;
+    let mut end = 1;
+    while end < dotted.len() {
+        let (next, len) = state.step(&dotted[end..])?;
+        state = next;
+        end += len;
+    }
+    Some(state)
+}
+
+/// Lowercase a word, normalize its apostrophes and add dots before and after
+/// it.
+///
+/// The dots enable patterns that match based on whether they are at the edges
+/// of the word.
+fn lowercase_and_dot(word: &str) -> Bytes {
+    let len = word.chars().map(|c| normalize(c).len_utf8()).sum::<usize>();
+    let mut dotted = Bytes::zeros(len + 2);
+    let dotted_mut = dotted.as_mut_slice();
+    dotted_mut[0] = b'.';
+
+    // Add the lowercased chars.
+    let mut offset = 1;
+    for c in word.chars() {
+        offset += normalize(c).encode_utf8(&mut dotted_mut[offset..]).len();
+    }
+
+    debug_assert_eq!(offset, len + 1);
+    dotted_mut[offset] = b'.';
+    dotted
+}
+
+/// Lowercase a char and turn a typographic apostrophe (U+2019) into an ASCII
+/// one, which is what the patterns use.
+fn normalize(c: char) -> char {
+    if c == '’' {
+        '\''
+    } else {
+        lowercase(c)
+    }
+}
+
+/// Lowercase a char if its lowercase form is a single char that is no longer
+/// in UTF-8, so that a lowercased word never needs more space than the word.
+///
+/// The lowercased word is walked in lockstep with the original one, so a
+/// shorter lowercase form doesn't shift any offsets. The capital I with dot
+/// above (U+0130) becomes a plain `i`, which is how patterns spell it, rather
+/// than an `i` followed by a combining dot.
+fn lowercase(c: char) -> char {
+    if c == 'İ' {
+        return 'i';
+    }
+
+    let mut lower = c.to_lowercase();
+    if let (Some(l), None) = (lower.next(), lower.next()) {
+        if l.len_utf8() <= c.len_utf8() {
+            return l;
+        }
+    }
+    c
+}
+
+/// Whether two strings are equal when lowercased.
+fn eq_ignore_case(a: &str, b: &str) -> bool {
+    a.chars()
+        .flat_map(char::to_lowercase)
+        .eq(b.chars().flat_map(char::to_lowercase))
+}
+
+/// An iterator over the syllables of a word.
+///
+/// This struct is created by [`hyphenate`] and [`hyphenate_bounded`].
+#[derive(Debug, Clone)]
+pub struct Syllables<'a> {
+    word: &'a str,
+    cursor: usize,
+    levels: Bytes,
+}
+
+impl Syllables<'_> {
+    /// Join the syllables with a separator like a hyphen or soft hyphen.
+    ///
+    /// This is only available when the `alloc` feature is enabled.
+    ///
+    /// # Example
+    /// Adding soft hyphens at every opportunity.
+    /// ```
+    /// # use hypher::{hyphenate, Lang};
+    /// # let joined =
+    /// hyphenate("wonderful", Lang::English).join("\u{ad}");
+    /// # assert_eq!(joined, "won\u{ad}der\u{ad}ful")
+    /// ```
+    #[cfg(any(feature = "alloc", test))]
+    pub fn join(mut self, sep: &str) -> alloc::string::String {
+        let extra = self.splits() * sep.len();
+        let mut s = alloc::string::String::with_capacity(self.word.len() + extra);
+        s.extend(self.next());
+        for syllable in self {
+            s.push_str(sep);
+            s.push_str(syllable);
+        }
+        s
+    }
+
+    /// The remaining number of splits in the word.
+    fn splits(&self) -> usize {
+        self.levels.as_slice().iter().filter(|&lvl| lvl % 2 == 1).count()
+    }
+
+    /// Turn the remaining syllables into the byte offsets between them.
+    fn into_positions(self) -> Positions {
+        let offset = self.word.len().saturating_sub(1) - self.levels.len();
+        Positions { offset, levels: self.levels }
+    }
+}
+
+impl<'a> Iterator for Syllables<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let found = self.levels.any(|lvl| lvl % 2 == 1);
+        let start = self.cursor;
+        let end = self.word.len() - self.levels.len() - found as usize;
+        self.cursor = end;
+        (start < end).then(|| &self.word[start..end])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = if self.cursor < self.word.len() { 1 + self.splits() } else { 0 };
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Syllables<'_> {}
+
+impl FusedIterator for Syllables<'_> {}
+
+/// An iterator over the syllables of a word with soft hyphens.
+///
+/// This struct is created by [`hyphenate_hinted`].
+#[derive(Debug, Clone)]
+pub struct HintedSyllables<'a> {
+    syllables: Syllables<'a>,
+}
+
+impl<'a> Iterator for HintedSyllables<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.syllables
+            .find(|syllable| syllable.chars().any(|c| c != SOFT_HYPHEN))
+    }
+}
+
+impl FusedIterator for HintedSyllables<'_> {}
+
+/// An iterator over the syllables of a word, each with whether a break
+/// follows it.
+///
+/// This struct is created by [`hyphenate_tagged`].
+#[derive(Debug, Clone)]
+pub struct TaggedSyllables<'a> {
+    syllables: Syllables<'a>,
+}
+
+impl<'a> Iterator for TaggedSyllables<'a> {
+    type Item = (&'a str, bool);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let syllable = self.syllables.next()?;
+        Some((syllable, self.syllables.len() > 0))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.syllables.size_hint()
+    }
+}
+
+impl ExactSizeIterator for TaggedSyllables<'_> {}
+
+impl FusedIterator for TaggedSyllables<'_> {}
+
+/// The syllables of a word as owned strings.
+///
+/// This is created from [`Syllables`] with [`From`] and is only available when
+/// the `alloc` feature is enabled. With the `serde` feature, it implements
+/// `Serialize` and `Deserialize`.
+///
+/// # Example
+/// ```
+/// # use hypher::{hyphenate, Hyphenated, Lang};
+/// let hyphenated = Hyphenated::from(hyphenate("wonderful", Lang::English));
+/// assert_eq!(hyphenated.syllables, ["won", "der", "ful"]);
+/// ```
+#[cfg(any(feature = "alloc", test))]
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hyphenated {
+    /// The syllables of the word.
+    pub syllables: alloc::vec::Vec<alloc::string::String>,
+}
+
+#[cfg(any(feature = "alloc", test))]
+impl From<Syllables<'_>> for Hyphenated {
+    fn from(syllables: Syllables) -> Self {
+        Self { syllables: syllables.map(Into::into).collect() }
+    }
+}
+
+/// An iterator over the byte offsets at which a word may be hyphenated.
+///
+/// This struct is created by [`hyphenate_positions`].
+#[derive(Debug, Clone)]
+pub struct Positions {
+    offset: usize,
+    levels: Bytes,
+}
+
+impl Iterator for Positions {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for lvl in self.levels.by_ref() {
+            self.offset += 1;
+            if lvl % 2 == 1 {
+                return Some(self.offset);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.levels.as_slice().iter().filter(|&lvl| lvl % 2 == 1).count();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Positions {}
+
+impl FusedIterator for Positions {}
+
+/// An iterator over the char indices at which a word may be hyphenated.
+///
+/// This struct is created by [`hyphenate_char_positions`].
+#[derive(Debug, Clone)]
+pub struct CharPositions<'a> {
+    word: &'a str,
+    offset: usize,
+    index: usize,
+    positions: Positions,
+}
+
+impl Iterator for CharPositions<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.positions.next()?;
+        self.index += self.word[self.offset..offset].chars().count();
+        self.offset = offset;
+        Some(self.index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.positions.size_hint()
+    }
+}
+
+impl ExactSizeIterator for CharPositions<'_> {}
+
+impl FusedIterator for CharPositions<'_> {}
+
+/// An iterator over the byte ranges of the syllables of a word.
+///
+/// This struct is created by [`hyphenate_ranges`].
+#[derive(Debug, Clone)]
+pub struct Ranges {
+    start: usize,
+    end: usize,
+    positions: Positions,
+}
+
+impl Iterator for Ranges {
+    type Item = core::ops::Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start >= self.end {
+            return None;
+        }
+        let end = self.positions.next().unwrap_or(self.end);
+        let range = self.start..end;
+        self.start = end;
+        Some(range)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = if self.start < self.end { 1 + self.positions.len() } else { 0 };
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Ranges {}
+
+impl FusedIterator for Ranges {}
+
+/// An iterator over the syllables of each token of another iterator.
+///
+/// This struct is created by [`HyphenateExt::hyphenate`]. The trie's root is
+/// decoded once up front and shared by all tokens.
+#[derive(Clone)]
+pub struct HyphenateIter<'a, I> {
+    tokens: I,
+    root: State<'a>,
+    left_min: usize,
+    right_min: usize,
+}
+
+impl<'a, I, T> Iterator for HyphenateIter<'a, I>
+where
+    I: Iterator<Item = &'a T>,
+    T: AsRef<str> + ?Sized + 'a,
+{
+    type Item = Syllables<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let word = self.tokens.next()?.as_ref();
+        Some(hyphenate_from(word, self.root, self.left_min, self.right_min, is_letter))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.tokens.size_hint()
+    }
+}
+
+impl<'a, I, T> ExactSizeIterator for HyphenateIter<'a, I>
+where
+    I: ExactSizeIterator<Item = &'a T>,
+    T: AsRef<str> + ?Sized + 'a,
+{
+}
+
+impl<'a, I, T> FusedIterator for HyphenateIter<'a, I>
+where
+    I: FusedIterator<Item = &'a T>,
+    T: AsRef<str> + ?Sized + 'a,
+{
+}
+
+impl<I: Debug> Debug for HyphenateIter<'_, I> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("HyphenateIter")
+            .field("tokens", &self.tokens)
+            .field("left_min", &self.left_min)
+            .field("right_min", &self.right_min)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The transition from the root below which exceptions are stored.
+///
+/// This byte never occurs in UTF-8, so it can't clash with a pattern.
+const EXCEPTION: u8 = 0xFF;
+
+/// The label of the [`EXCEPTION`] transition in a trie whose transitions are
+/// labelled with chars. It lies outside of the range of chars.
+const EXCEPTION_CHAR: u32 = 0xFF_FFFF;
+
+/// The maximum size (in bytes) of words that may be hyphenated without
+/// allocating.
+pub const MAX_INLINE_SIZE: usize = 45;
+const INLINE_BUF_SIZE: usize = MAX_INLINE_SIZE + 2; // +2 for dots
+
+/// Storage for and iterator over bytes.
+#[derive(Clone)]
+enum Bytes {
+    Array([u8; INLINE_BUF_SIZE], NonZeroU8),
+    #[cfg(feature = "alloc")]
+    Vec(alloc::vec::IntoIter<u8>),
+}
+
+impl Bytes {
+    /// Create zero-initialized bytes.
+    fn zeros(len: usize) -> Self {
+        if len <= INLINE_BUF_SIZE {
             // MAX+1-MAX is still nonzero, we can unwrap
             let start = NonZeroU8::new(INLINE_BUF_SIZE as u8 + 1 - len as u8).unwrap();
             Self::Array([0; INLINE_BUF_SIZE], start)
@@ -317,39 +1681,594 @@ impl Bytes {
     }
 }
 
-impl Iterator for Bytes {
-    type Item = u8;
+impl Iterator for Bytes {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Array(arr, start) => {
+                let index = start.get() as usize - 1;
+                if index < INLINE_BUF_SIZE {
+                    *start = start.saturating_add(1); // Will never reach 255 anyways.
+                    Some(arr[index])
+                } else {
+                    None
+                }
+            }
+            #[cfg(feature = "alloc")]
+            Self::Vec(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Array(..) => (self.as_slice().len(), Some(self.as_slice().len())),
+            #[cfg(feature = "alloc")]
+            Self::Vec(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl ExactSizeIterator for Bytes {}
+
+impl Debug for Bytes {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+/// The magic bytes at the start of an encoded trie.
+const MAGIC: [u8; 4] = *b"HYPH";
+
+/// The newest version of the trie encoding, stored after the magic bytes.
+///
+/// Tries with any of the [`LAYOUT_FLAGS`] need this version to be decoded.
+/// Other tries are stored as [`MIN_VERSION`], so that older versions of
+/// hypher can still load them.
+const VERSION: u8 = 3;
+
+/// The oldest version of the trie encoding that can still be decoded.
+const MIN_VERSION: u8 = 2;
+
+/// The length of the fixed-size header at the start of an encoded trie: magic
+/// bytes, version, two bytes of flags and the (left,right)-hyphenmin.
+///
+/// With [`FLAG_METADATA`], it is followed by the metadata. The encoded nodes
+/// come after that.
+const HEADER_LEN: usize = MAGIC.len() + 5;
+
+/// An encoded trie of hyphenation patterns.
+///
+/// Tries are built by the `builder` module of the `build` feature. Each
+/// built-in language also has one, which you can get with [`Lang::trie`].
+///
+/// A trie is just a reference to its encoded bytes, which hyphenation walks
+/// directly without decoding them up front. There is thus nothing to cache
+/// between calls, and tries can be shared freely between threads.
+///
+/// # Example
+/// ```
+/// # use hypher::{Lang, Trie};
+/// let bytes = Lang::English.trie().as_bytes();
+/// let trie = Trie::from_bytes(bytes).unwrap();
+/// assert_eq!(trie.bounds(), (2, 3));
+/// assert_eq!(trie.hyphenate("extensive").collect::<Vec<_>>(), ["ex", "ten", "sive"]);
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Trie<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Trie<'a> {
+    /// Load a trie from its encoded bytes.
+    ///
+    /// Pass as `bytes` the output of `hypher::builder::build_trie` or an
+    /// equivalently obtained well-formed trie.
+    ///
+    /// Fails if `bytes` don't start with the header of a trie of a supported
+    /// format version or if its metadata or root node can't be decoded. No further
+    /// validation will occur here: if you provide a malformed automata,
+    /// hyphenation won't panic but may yield nonsensical syllables.
+    ///
+    /// Tries built by hypher 0.1 have no header and fail with
+    /// [`TrieError::NotATrie`]. Rebuild them from their pattern file, with
+    /// `hypher::builder::build_trie_with` to keep the hyphenmins they used.
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, TrieError> {
+        check_header(data)?;
+        State::root(data).ok_or(TrieError::Malformed)?;
+        Ok(Self { data })
+    }
+
+    /// Load a trie from its encoded bytes, verifying their checksum.
+    ///
+    /// Like [`from_bytes`](Self::from_bytes), but also fails if the trie
+    /// doesn't end with a checksum or it doesn't match the data. Build the
+    /// trie with `TrieBuilder::set_checksum` to add one.
+    pub fn from_bytes_checked(data: &'a [u8]) -> Result<Self, TrieError> {
+        check_checksum(data)?;
+        Self::from_bytes(data)
+    }
+
+    /// The encoded bytes of the trie.
+    pub fn as_bytes(self) -> &'a [u8] {
+        self.data
+    }
+
+    /// The (left,right)-hyphenmin stored in the trie.
+    pub fn bounds(self) -> (usize, usize) {
+        stored_bounds(self.data)
+    }
+
+    /// The metadata stored in the trie, like the name of the language or the
+    /// source of its patterns.
+    ///
+    /// This is empty unless the trie was built with
+    /// `TrieBuilder::set_metadata`.
+    pub fn metadata(self) -> &'a str {
+        stored_metadata(self.data).unwrap_or_default()
+    }
+
+    /// Segment a word into syllables, using the trie's [bounds](Self::bounds).
+    ///
+    /// See [`hyphenate`] for details.
+    pub fn hyphenate(self, word: &'a str) -> Syllables<'a> {
+        let (left_min, right_min) = self.bounds();
+        self.hyphenate_bounded(word, left_min, right_min)
+    }
+
+    /// Segment a word into syllables, but forbid breaking between the given
+    /// number of chars to each side.
+    ///
+    /// See [`hyphenate_bounded`] for details.
+    pub fn hyphenate_bounded(
+        self,
+        word: &'a str,
+        left_min: usize,
+        right_min: usize,
+    ) -> Syllables<'a> {
+        hyphenate_from(word, self.root(), left_min, right_min, is_letter)
+    }
+
+    /// A cursor at the root of the trie, to walk it manually.
+    pub fn cursor(self) -> Cursor<'a> {
+        Cursor { state: self.root() }
+    }
+
+    /// Entry point of the automata.
+    fn root(self) -> State<'a> {
+        // Built-in tries are well-formed and `from_bytes` checked the root of
+        // loaded ones.
+        State::root(self.data).expect("root node of trie is malformed")
+    }
+}
+
+/// A node of a trie, for walking it manually.
+///
+/// This is created by [`Trie::cursor`] and exposes how hyphenation navigates
+/// the trie, for example for custom matching algorithms. Each path from the
+/// root spells out the lowercase letters of a pattern, with dots for the
+/// start and end of a word, and the node it leads to holds the levels of
+/// that pattern.
+///
+/// # Example
+/// ```
+/// # use hypher::Lang;
+/// let cursor = Lang::English.trie().cursor();
+/// let node = "hyph".bytes().try_fold(cursor, |node, b| node.transition(b)).unwrap();
+/// assert_eq!(node.levels().collect::<Vec<_>>(), [(2, 3)]); // hy3ph
+/// ```
+#[derive(Copy, Clone)]
+pub struct Cursor<'a> {
+    state: State<'a>,
+}
+
+impl<'a> Cursor<'a> {
+    /// Follow the transition labelled with a byte of the UTF-8 encoding of a
+    /// pattern.
+    ///
+    /// Returns `None` if there is no such transition or the trie is
+    /// malformed. In a trie whose transitions are labelled with whole chars,
+    /// the byte is matched as a char, so only ASCII bytes work there. Use
+    /// [`transition_char`](Self::transition_char) for other chars.
+    pub fn transition(self, byte: u8) -> Option<Self> {
+        self.state.transition(byte.into()).map(|state| Self { state })
+    }
+
+    /// Follow the transitions for a char, in any kind of trie.
+    ///
+    /// Returns `None` if there is no such transition or the trie is
+    /// malformed.
+    pub fn transition_char(self, c: char) -> Option<Self> {
+        if self.state.width == 1 {
+            let mut buf = [0; 4];
+            c.encode_utf8(&mut buf).bytes().try_fold(self, Self::transition)
+        } else {
+            self.state.transition(c.into()).map(|state| Self { state })
+        }
+    }
+
+    /// The levels of the pattern that ends at this node, if any.
+    ///
+    /// Yields each level together with its distance in bytes from the
+    /// previous level or, for the first level, from the start of the pattern.
+    /// The level is between the bytes before and after that point. Levels of
+    /// zero are not stored.
+    pub fn levels(self) -> impl Iterator<Item = (usize, u8)> + 'a {
+        self.state
+            .levels
+            .iter()
+            .map(|&packed| (usize::from(packed / 10), packed % 10))
+    }
+}
+
+impl Debug for Cursor<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Cursor")
+            .field("addr", &self.state.addr)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "dyn")]
+impl<'a> From<Trie<'a>> for Lang<'a> {
+    fn from(trie: Trie<'a>) -> Self {
+        Self::Dyn(trie)
+    }
+}
+
+/// The header flag marking that a trie ends with a CRC-32 checksum of
+/// everything after the header.
+const FLAG_CHECKSUM: u8 = 1;
+
+/// The header flags that change how the rest of the trie is decoded. Readers
+/// that don't know them would misread the trie, so a trie with any of them is
+/// stored with the newest [`VERSION`].
+const LAYOUT_FLAGS: u8 = FLAG_CHARS | FLAG_METADATA;
+
+/// All header flags this version of hypher knows about.
+const KNOWN_FLAGS: u8 = FLAG_CHECKSUM | FLAG_CHARS | FLAG_METADATA;
+
+/// The header flag marking that the transitions of a trie are labelled with
+/// whole chars, stored as three-byte code points, instead of single bytes of
+/// their UTF-8 encoding.
+const FLAG_CHARS: u8 = 2;
+
+/// The header flag marking that the fixed-size header of a trie is followed
+/// by metadata. It is covered by the checksum like the nodes.
+const FLAG_METADATA: u8 = 4;
+
+/// Read the format version from the header of an encoded trie.
+///
+/// Unlike [`Trie::from_bytes`], this only looks at the magic bytes and the
+/// version that follows them, so it also works for tries of other versions,
+/// for example to decide whether they need to be rebuilt. Returns `None` if
+/// `data` doesn't start with the magic bytes of a trie.
+///
+/// The version is the oldest one that can decode the trie. Plain tries are
+/// stored as version 2, which all versions of hypher with this header can
+/// load. Tries with char-labelled transitions or metadata are stored as
+/// version 3 and need a version of hypher that supports them.
+///
+/// # Example
+/// ```
+/// # use hypher::{trie_version, Lang};
+/// assert_eq!(trie_version(Lang::English.trie().as_bytes()), Some(2));
+/// assert_eq!(trie_version(b"not a trie"), None);
+/// ```
+pub fn trie_version(data: &[u8]) -> Option<u8> {
+    if !data.starts_with(&MAGIC) {
+        return None;
+    }
+    data.get(MAGIC.len()).copied()
+}
+
+/// Check that `data` starts with a header for a trie this version of hypher
+/// can decode.
+fn check_header(data: &[u8]) -> Result<(), TrieError> {
+    if data.len() < HEADER_LEN + 4 || data[..MAGIC.len()] != MAGIC {
+        return Err(TrieError::NotATrie);
+    }
+
+    let version = data[MAGIC.len()];
+    if !(MIN_VERSION..=VERSION).contains(&version) {
+        return Err(TrieError::UnsupportedVersion(version));
+    }
+
+    let unknown = u16::from_be_bytes([data[MAGIC.len() + 2], flags(data) & !KNOWN_FLAGS]);
+    if unknown != 0 {
+        return Err(TrieError::UnsupportedFlags(unknown));
+    }
+
+    // Older versions didn't have the layout flags.
+    if version < VERSION && flags(data) & LAYOUT_FLAGS != 0 {
+        return Err(TrieError::Malformed);
+    }
+
+    Ok(())
+}
+
+/// Check that a trie ends with a checksum that matches its contents.
+fn check_checksum(data: &[u8]) -> Result<(), TrieError> {
+    check_header(data)?;
+    if flags(data) & FLAG_CHECKSUM == 0 {
+        return Err(TrieError::MissingChecksum);
+    }
+
+    let (body, crc) = data[HEADER_LEN..].split_at(data.len() - HEADER_LEN - 4);
+    if body.len() < 4 || crc32(body).to_be_bytes() != crc {
+        return Err(TrieError::ChecksumMismatch);
+    }
+
+    Ok(())
+}
+
+/// Compute the CRC-32 (as used by zlib) of some bytes.
+fn crc32(data: &[u8]) -> u32 {
+    !crc32_update(!0, data)
+}
+
+/// Feed more bytes into a running CRC-32 computation.
+///
+/// Start with `!0` and invert the result after the last bytes.
+fn crc32_update(mut crc: u32, data: &[u8]) -> u32 {
+    for &b in data {
+        crc ^= u32::from(b);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB88320 & (crc & 1).wrapping_neg());
+        }
+    }
+    crc
+}
+
+/// Read the flags stored in the header of a trie.
+fn flags(data: &[u8]) -> u8 {
+    data[MAGIC.len() + 1]
+}
+
+/// Read the metadata following the fixed-size header of a trie, which is
+/// empty if there is none.
+///
+/// The metadata is prefixed with its length as a big-endian `u16`. Returns
+/// `None` if it is out of bounds or not valid UTF-8.
+fn stored_metadata(data: &[u8]) -> Option<&str> {
+    if data.get(MAGIC.len() + 1)? & FLAG_METADATA == 0 {
+        return Some("");
+    }
+
+    let len = data.get(HEADER_LEN..HEADER_LEN + 2)?;
+    let len = usize::from(u16::from_be_bytes([len[0], len[1]]));
+    core::str::from_utf8(data.get(HEADER_LEN + 2..HEADER_LEN + 2 + len)?).ok()
+}
+
+/// The length of the whole header of a trie, including the metadata.
+///
+/// Returns `None` if the metadata is malformed.
+fn header_len(data: &[u8]) -> Option<usize> {
+    let metadata = stored_metadata(data)?;
+    if data[MAGIC.len() + 1] & FLAG_METADATA == 0 {
+        Some(HEADER_LEN)
+    } else {
+        Some(HEADER_LEN + 2 + metadata.len())
+    }
+}
+
+/// Read the (left,right)-hyphenmin stored in the header of a trie.
+fn stored_bounds(data: &[u8]) -> (usize, usize) {
+    let offset = MAGIC.len() + 3;
+    (usize::from(data[offset]), usize::from(data[offset + 1]))
+}
+
+/// An error that can occur when loading an encoded trie.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TrieError {
+    /// The data doesn't start with the magic bytes of a trie.
+    NotATrie,
+    /// The trie was encoded with an unsupported version of the format.
+    UnsupportedVersion(u8),
+    /// The trie uses features of the format unknown to this version of
+    /// hypher, given by their flag bits.
+    UnsupportedFlags(u16),
+    /// The trie has no checksum to verify.
+    MissingChecksum,
+    /// The checksum of the trie doesn't match its contents.
+    ChecksumMismatch,
+    /// A node of the trie or its levels are out of bounds of the data.
+    Malformed,
+}
+
+impl Display for TrieError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::NotATrie => f.write_str("not a hypher trie file"),
+            Self::UnsupportedVersion(version) => {
+                write!(
+                    f,
+                    "unsupported trie format version {version} \
+                     (expected {MIN_VERSION} to {VERSION})"
+                )
+            }
+            Self::UnsupportedFlags(flags) => {
+                write!(f, "unsupported trie format flags {flags:#06x}")
+            }
+            Self::MissingChecksum => f.write_str("trie has no checksum"),
+            Self::ChecksumMismatch => {
+                f.write_str("trie checksum mismatch, data is corrupted")
+            }
+            Self::Malformed => f.write_str("trie data is malformed"),
+        }
+    }
+}
+
+#[cfg(any(feature = "build", test))]
+impl std::error::Error for TrieError {}
+
+impl core::str::FromStr for Lang<'_> {
+    type Err = ParseLangError;
+
+    /// Select a language using its ISO 639-1 code, like `"en"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code: [u8; 2] =
+            s.as_bytes().try_into().map_err(|_| ParseLangError::InvalidCode)?;
+        Lang::from_iso(code).ok_or(ParseLangError::UnknownLanguage)
+    }
+}
+
+impl Display for Lang<'_> {
+    /// Writes the ISO 639-1 code of the language, or `dyn` for dynamically
+    /// loaded patterns.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.iso() {
+            Some(code) => code.iter().try_for_each(|&b| write!(f, "{}", b as char)),
+            None => f.write_str("dyn"),
+        }
+    }
+}
+
+/// Serializes the language as its ISO 639-1 code.
+///
+/// Fails for dynamically loaded patterns, which have no code.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Lang<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let code = self
+            .iso()
+            .ok_or_else(|| serde::ser::Error::custom("patterns have no ISO code"))?;
+        let code = core::str::from_utf8(&code).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(code)
+    }
+}
+
+/// Deserializes a language from its ISO 639-1 code.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Lang<'_> {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = Lang<'static>;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str("an ISO 639-1 language code")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
+
+/// An error that can occur when parsing a [`Lang`] from a string.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ParseLangError {
+    /// The string is not a two-letter code.
+    InvalidCode,
+    /// No enabled language has this code.
+    UnknownLanguage,
+}
+
+impl Display for ParseLangError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidCode => f.write_str("not a two-letter ISO 639-1 code"),
+            Self::UnknownLanguage => {
+                f.write_str("no language with this code is available")
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "build", test))]
+impl std::error::Error for ParseLangError {}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self {
-            Self::Array(arr, start) => {
-                let index = start.get() as usize - 1;
-                if index < INLINE_BUF_SIZE {
-                    *start = start.saturating_add(1); // Will never reach 255 anyways.
-                    Some(arr[index])
-                } else {
-                    None
-                }
-            }
-            #[cfg(feature = "alloc")]
-            Self::Vec(iter) => iter.next(),
+/// A script that languages are written in.
+///
+/// This is returned by [`Lang::script`] and can be used with
+/// [`Lang::for_script`] to pick a language for text whose script is known, but
+/// not its language.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Script {
+    /// The Latin script. (Code: `Latn`)
+    Latin,
+    /// The Cyrillic script. (Code: `Cyrl`)
+    Cyrillic,
+    /// The Greek script. (Code: `Grek`)
+    Greek,
+    /// The Georgian script. (Code: `Geor`)
+    Georgian,
+}
+
+impl Script {
+    /// The script a char is written in.
+    ///
+    /// Returns `None` for chars that are shared by all scripts, like digits
+    /// and punctuation, and for chars of other scripts.
+    ///
+    /// # Example
+    /// ```
+    /// # use hypher::Script;
+    /// assert_eq!(Script::of('a'), Some(Script::Latin));
+    /// assert_eq!(Script::of('я'), Some(Script::Cyrillic));
+    /// assert_eq!(Script::of('7'), None);
+    /// ```
+    pub fn of(c: char) -> Option<Self> {
+        match c {
+            'A'..='Z' | 'a'..='z' | 'ª' | 'º' => Some(Self::Latin),
+            '\u{c0}'..='\u{24f}' if c != '×' && c != '÷' => Some(Self::Latin),
+            '\u{1e00}'..='\u{1eff}' | '\u{2c60}'..='\u{2c7f}' => Some(Self::Latin),
+            '\u{a720}'..='\u{a7ff}' | '\u{ff21}'..='\u{ff3a}' => Some(Self::Latin),
+            '\u{ff41}'..='\u{ff5a}' => Some(Self::Latin),
+            '\u{370}'..='\u{3ff}' | '\u{1f00}'..='\u{1fff}' => Some(Self::Greek),
+            '\u{400}'..='\u{52f}' | '\u{1c80}'..='\u{1c8f}' => Some(Self::Cyrillic),
+            '\u{2de0}'..='\u{2dff}' | '\u{a640}'..='\u{a69f}' => Some(Self::Cyrillic),
+            '\u{10a0}'..='\u{10ff}' | '\u{1c90}'..='\u{1cbf}' => Some(Self::Georgian),
+            '\u{2d00}'..='\u{2d2f}' => Some(Self::Georgian),
+            _ => None,
         }
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
+    /// The ISO 15924 four letter code of the script.
+    pub fn code(self) -> [u8; 4] {
         match self {
-            Self::Array(..) => (self.as_slice().len(), Some(self.as_slice().len())),
-            #[cfg(feature = "alloc")]
-            Self::Vec(iter) => iter.size_hint(),
+            Self::Latin => *b"Latn",
+            Self::Cyrillic => *b"Cyrl",
+            Self::Greek => *b"Grek",
+            Self::Georgian => *b"Geor",
         }
     }
 }
 
-impl ExactSizeIterator for Bytes {}
-
-impl Debug for Bytes {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        self.as_slice().fmt(f)
+impl Lang<'static> {
+    /// Select a default language for text in the given script.
+    ///
+    /// This is English for Latin, Russian for Cyrillic, Greek for Greek and
+    /// Georgian for Georgian. If the feature of that language is disabled,
+    /// the first enabled language written in the script is picked instead.
+    /// Returns `None` if there is none.
+    ///
+    /// # Example
+    /// ```
+    /// # use hypher::{Lang, Script};
+    /// # #[cfg(feature = "russian")] {
+    /// let lang = Lang::for_script(Script::Cyrillic).unwrap();
+    /// assert_eq!(lang, Lang::Russian);
+    /// assert_eq!(lang.script(), Some(Script::Cyrillic));
+    /// # }
+    /// ```
+    pub fn for_script(script: Script) -> Option<Self> {
+        let preferred = match script {
+            Script::Latin => *b"en",
+            Script::Cyrillic => *b"ru",
+            Script::Greek => *b"el",
+            Script::Georgian => *b"ka",
+        };
+        Self::from_iso(preferred)
+            .or_else(|| Self::iter().find(|lang| lang.script() == Some(script)))
     }
 }
 
@@ -359,68 +2278,122 @@ struct State<'a> {
     data: &'a [u8],
     addr: usize,
     stride: usize,
+    width: usize,
     levels: &'a [u8],
     trans: &'a [u8],
     targets: &'a [u8],
 }
 
 impl<'a> State<'a> {
-    /// Create a new state at the root node.
-    #[allow(unused)]
-    fn root(data: &'a [u8]) -> Self {
-        let bytes = data[..4].try_into().unwrap();
+    /// Create a new state at the root node of an encoded trie, skipping its
+    /// header.
+    ///
+    /// Returns `None` if the metadata is malformed or the root node is out of
+    /// bounds.
+    fn root(data: &'a [u8]) -> Option<Self> {
+        let width = if flags(data.get(..HEADER_LEN)?) & FLAG_CHARS != 0 { 3 } else { 1 };
+        let data = data.get(header_len(data)?..)?;
+        let bytes = data.get(..4)?.try_into().ok()?;
         let addr = u32::from_be_bytes(bytes) as usize;
-        Self::at(data, addr)
+        Self::at(data, addr, width)
     }
 
-    /// Create a new state at the given node address.
-    fn at(data: &'a [u8], addr: usize) -> Self {
-        let node = &data[addr..];
+    /// Create a new state at the given node address, whose transitions are
+    /// labelled with `width` bytes each.
+    ///
+    /// Returns `None` if the node or its levels are out of bounds.
+    fn at(data: &'a [u8], addr: usize, width: usize) -> Option<Self> {
+        let node = data.get(addr..)?;
         let mut pos = 0;
 
         // Decode whether the state has levels and the transition count.
-        let has_levels = node[pos] >> 7 != 0;
-        let stride = usize::from((node[pos] >> 5) & 3);
-        let mut count = usize::from(node[pos] & 31);
+        let header = *node.first()?;
+        let has_levels = header >> 7 != 0;
+        let stride = usize::from((header >> 5) & 3) + 1;
+        let mut count = usize::from(header & 31);
         pos += 1;
 
         // Possibly decode high transition count.
         if count == 31 {
-            count = usize::from(node[pos]);
+            count = usize::from(*node.get(pos)?);
             pos += 1;
         }
 
         // Decode the levels.
         let mut levels: &[u8] = &[];
         if has_levels {
-            let offset_hi = usize::from(node[pos]) << 4;
-            let offset_lo = usize::from(node[pos + 1]) >> 4;
+            let packed = node.get(pos..pos + 2)?;
+            let offset_hi = usize::from(packed[0]) << 4;
+            let offset_lo = usize::from(packed[1]) >> 4;
             let offset = offset_hi | offset_lo;
-            let len = usize::from(node[pos + 1] & 15);
-            levels = &data[offset..offset + len];
+            let len = usize::from(packed[1] & 15);
+            levels = data.get(offset..offset + len)?;
             pos += 2;
         }
 
         // Decode the transitions.
-        let trans = &node[pos..pos + count];
-        pos += count;
+        let trans = node.get(pos..pos + width * count)?;
+        pos += width * count;
 
         // Decode the targets.
-        let targets = &node[pos..pos + stride * count];
-        Self { data, addr, stride, levels, trans, targets }
-    }
-
-    /// Return the state reached by following the transition labelled `b`.
-    /// Returns `None` if there is no such state.
-    fn transition(self, b: u8) -> Option<Self> {
-        self.trans.iter().position(|&x| x == b).map(|idx| {
-            let offset = self.stride * idx;
-            let delta = from_be_bytes(&self.targets[offset..offset + self.stride]);
-            let next = (self.addr as isize + delta) as usize;
-            Self::at(self.data, next)
+        let targets = node.get(pos..pos + stride * count)?;
+        Some(Self { data, addr, stride, width, levels, trans, targets })
+    }
+
+    /// Return the state reached by following the transition labelled `label`.
+    /// Returns `None` if there is no such state or it is out of bounds.
+    fn transition(self, label: u32) -> Option<Self> {
+        let idx = if self.width == 1 {
+            let b = u8::try_from(label).ok()?;
+            self.trans.iter().position(|&x| x == b)?
+        } else {
+            self.trans.chunks_exact(3).position(|x| from_be_u24(x) == label)?
+        };
+        let offset = self.stride * idx;
+        let delta = from_be_bytes(&self.targets[offset..offset + self.stride]);
+        let next = (self.addr as isize).checked_add(delta)?;
+        Self::at(self.data, usize::try_from(next).ok()?, self.width)
+    }
+
+    /// Follow the transition for the label at the start of `bytes`.
+    ///
+    /// Returns the next state and how many bytes the label spans: always one
+    /// in a byte trie, but a whole char in a char trie.
+    fn step(self, bytes: &[u8]) -> Option<(Self, usize)> {
+        let &first = bytes.first()?;
+        if self.width == 1 {
+            return Some((self.transition(first.into())?, 1));
+        }
+
+        if first == EXCEPTION {
+            return Some((self.transition(EXCEPTION_CHAR)?, 1));
+        }
+
+        let len = utf8_len(first)?;
+        let c = core::str::from_utf8(bytes.get(..len)?).ok()?.chars().next()?;
+        Some((self.transition(c.into())?, len))
+    }
+
+    /// The labels of the state's transitions, in encoded order.
+    #[cfg(any(feature = "build", test))]
+    fn labels(self) -> impl Iterator<Item = u32> + 'a {
+        let width = self.width;
+        self.trans.chunks_exact(width).map(move |x| {
+            if width == 1 {
+                x[0].into()
+            } else {
+                from_be_u24(x)
+            }
         })
     }
 
+    /// Whether a pattern or exception ends in this state, even if it has no
+    /// levels.
+    #[cfg(any(feature = "alloc", feature = "build", test))]
+    fn is_final(self) -> bool {
+        self.data[self.addr] >> 7 != 0
+    }
+
     /// Returns the levels contained in the state.
     fn levels(self) -> impl Iterator<Item = (usize, u8)> + 'a {
         let mut offset = 0;
@@ -433,7 +2406,7 @@ impl<'a> State<'a> {
     }
 }
 
-/// Decode a signed number with 1, 2 or 3 bytes.
+/// Decode a signed number with 1, 2, 3 or 4 bytes.
 fn from_be_bytes(buf: &[u8]) -> isize {
     if let Ok(array) = buf.try_into() {
         i8::from_be_bytes(array) as isize
@@ -445,11 +2418,30 @@ fn from_be_bytes(buf: &[u8]) -> isize {
         let third = usize::from(buf[2]);
         let unsigned = first | second | third;
         unsigned as isize - (1 << 23)
+    } else if let Ok(array) = buf.try_into() {
+        i32::from_be_bytes(array) as isize
     } else {
         panic!("invalid stride");
     }
 }
 
+/// Decode an unsigned number with 3 bytes.
+fn from_be_u24(buf: &[u8]) -> u32 {
+    u32::from(buf[0]) << 16 | u32::from(buf[1]) << 8 | u32::from(buf[2])
+}
+
+/// The length of the UTF-8 sequence starting with the given byte or `None` if
+/// it can't start one.
+fn utf8_len(b: u8) -> Option<usize> {
+    match b {
+        0x00..=0x7F => Some(1),
+        0xC0..=0xDF => Some(2),
+        0xE0..=0xEF => Some(3),
+        0xF0..=0xF7 => Some(4),
+        _ => None,
+    }
+}
+
 /// Whether a byte is a character boundary.
 fn is_char_boundary(b: u8) -> bool {
     (b as i8) >= -0x40
@@ -457,7 +2449,28 @@ fn is_char_boundary(b: u8) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{hyphenate, Lang, MAX_INLINE_SIZE};
+    use super::{
+        hyphenate, hyphenate_bounded, hyphenate_classified, hyphenate_cow,
+        hyphenate_hinted, hyphenate_min_len, hyphenate_positions, hyphenate_soft,
+        hyphenate_string, hyphenate_tagged, is_letter, matching_patterns, Cursor,
+        Exceptions, Hints, HyphenateExt, Hyphenated, Lang, ParseLangError, Script, State,
+        Trie, TrieError, HEADER_LEN, MAX_INLINE_SIZE,
+    };
+
+    #[cfg(feature = "german")]
+    use super::hyphenate_char_positions;
+
+    #[cfg(all(feature = "english", feature = "french"))]
+    use super::hyphenate_fallback;
+
+    #[cfg(all(feature = "english", feature = "german"))]
+    use super::{hyphenate_best_break, hyphenate_max_breaks, hyphenate_ranges};
+
+    #[cfg(all(feature = "english", feature = "russian"))]
+    use super::hyphenate_mixed;
+
+    #[cfg(all(feature = "english", feature = "german", feature = "russian"))]
+    use super::hyphenate_bytes;
 
     #[allow(unused)]
     use Lang::*;
@@ -482,6 +2495,116 @@ mod tests {
         assert_eq!(hyphenate("", English).len(), 0);
         assert_eq!(hyphenate("hello", English).len(), 1);
         assert_eq!(hyphenate("extensive", English).len(), 3);
+
+        let mut syllables = hyphenate("extensive", English);
+        for len in (0..3).rev() {
+            syllables.next();
+            assert_eq!(syllables.len(), len);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_tagged() {
+        let tagged: Vec<_> = hyphenate_tagged("information", English).collect();
+        assert_eq!(tagged, [("in", true), ("for", true), ("ma", true), ("tion", false)]);
+
+        let tagged: Vec<_> = hyphenate_tagged("uncharacteristically", English).collect();
+        assert_eq!(
+            tagged,
+            [
+                ("un", true),
+                ("char", true),
+                ("ac", true),
+                ("ter", true),
+                ("is", true),
+                ("ti", true),
+                ("cally", false),
+            ]
+        );
+
+        let tagged: Vec<_> = hyphenate_tagged("hyphenation", English).collect();
+        assert_eq!(tagged, [("hy", true), ("phen", true), ("ation", false)]);
+        let tagged: Vec<_> = hyphenate_tagged("hello", English).collect();
+        assert_eq!(tagged, [("hello", false)]);
+        assert_eq!(hyphenate_tagged("", English).next(), None);
+
+        let mut tagged = hyphenate_tagged("wonderful", English);
+        assert_eq!(tagged.len(), 3);
+        tagged.next();
+        assert_eq!(tagged.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_cursor() {
+        fn walk<'a>(cursor: Cursor<'a>, pattern: &str) -> Option<Cursor<'a>> {
+            pattern.bytes().try_fold(cursor, Cursor::transition)
+        }
+        let levels = |cursor: Cursor| cursor.levels().collect::<Vec<_>>();
+
+        let root = English.trie().cursor();
+        assert_eq!(levels(walk(root, ".he").unwrap()), [(3, 2)]);
+        let hy = walk(root, "hy").unwrap();
+        assert_eq!(levels(hy), []);
+        assert_eq!(levels(walk(hy, "ph").unwrap()), [(2, 3)]);
+        assert_eq!(levels(walk(root, "hena").unwrap()), [(4, 4)]);
+        assert_eq!(levels(walk(root, "hen").unwrap()), [(2, 2)]);
+        assert!(walk(hy, "qq").is_none());
+        assert!(root.transition(b'Q').is_none());
+
+        // Chars work in byte and char tries alike.
+        let mut builder = crate::builder::TrieBuilder::new();
+        builder.insert_tex(r"\patterns{ 1ä1b }").unwrap();
+        let bytes = builder.encode().unwrap();
+        builder.set_chars(true);
+        let chars = builder.encode().unwrap();
+        for trie in [&bytes, &chars] {
+            let cursor = Trie::from_bytes(trie).unwrap().cursor();
+            let node = cursor.transition_char('ä').and_then(|c| c.transition_char('b'));
+            assert_eq!(levels(node.unwrap()), [(0, 1), (2, 1)]);
+        }
+        let cursor = Trie::from_bytes(&chars).unwrap().cursor();
+        assert!(cursor.transition("ä".as_bytes()[0]).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_exception_boundaries() {
+        // Exceptions only match whole words, ignoring case.
+        test(English, "as-so-ciate");
+        test(English, "As-so-ciate");
+        assert_eq!(matching_patterns("associate", English), ["as-so-ciate"]);
+
+        // Longer words containing an exception use the patterns.
+        test(English, "dis-as-so-ci-ate");
+        test(English, "re-as-so-ci-ate");
+        test(English, "as-so-ci-ated");
+        for word in ["disassociate", "reassociate", "associated"] {
+            let patterns = matching_patterns(word, English);
+            assert!(patterns.iter().all(|pattern| !pattern.contains('-')));
+        }
+    }
+
+    #[test]
+    fn test_degenerate() {
+        // Every inner char boundary is a possible break.
+        let trie = crate::builder::build_trie(r"\patterns{ 1b 1c 1d 1e }").unwrap();
+        let trie = Trie::from_bytes(&trie).unwrap();
+        let bounded = |word, left_min, right_min| {
+            trie.hyphenate_bounded(word, left_min, right_min).collect::<Vec<_>>()
+        };
+
+        assert_eq!(bounded("", 2, 3), [""; 0]);
+        assert_eq!(bounded("", 0, 0), [""; 0]);
+        assert_eq!(bounded("a", 2, 3), ["a"]);
+        assert_eq!(bounded("a", 0, 0), ["a"]);
+        assert_eq!(bounded("ä", 1, 1), ["ä"]);
+        assert_eq!(bounded("abcd", 2, 3), ["abcd"]);
+        assert_eq!(bounded("abcde", 2, 3), ["ab", "cde"]);
+        assert_eq!(bounded("abcdé", 2, 3), ["ab", "cdé"]);
+        assert_eq!(bounded("abcde", usize::MAX, usize::MAX), ["abcde"]);
+        assert_eq!(bounded("a-b", 1, 1), ["a-b"]);
     }
 
     const LONG_WORD: &str = "thisisaverylongstringwithanunrealisticwordlengthforenglishbutitmightbepossibleinanotherlanguage";
@@ -523,6 +2646,506 @@ mod tests {
         test(English, "rec-og-nize");
     }
 
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_english_exceptions() {
+        test(English, "as-so-ciate");
+        test(English, "As-so-ciates");
+        test(English, "project");
+        test(English, "ta-ble");
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_case() {
+        test(English, "Hy-phen-ation");
+        test(English, "HY-PHEN-ATION");
+        test(English, "ABCDEF");
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_case_mixed() {
+        test(English, "Mc-Don-ald");
+        test(English, "NASA");
+        test(English, "In-ter-Na-tional");
+        for word in ["McDonald", "NASA", "InterNational"] {
+            assert!(hyphenate_positions(word, English)
+                .eq(hyphenate_positions(&word.to_lowercase(), English)));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "turkish")]
+    fn test_case_turkish() {
+        // The capital I with dot above is longer in UTF-8 than `i`.
+        test(Turkish, "İKİN-Cİ");
+        test(Turkish, "İs-tan-bul");
+        test(Turkish, "Kİ-TAP-LIK");
+        assert_eq!(hyphenate_positions("İKİNCİ", Turkish).collect::<Vec<_>>(), [6]);
+    }
+
+    #[test]
+    #[cfg(feature = "german")]
+    fn test_case_german() {
+        test(German, "Äp-fel-bäu-me");
+        test(German, "ÜBER-ZEU-GEN-DER");
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_positions() {
+        let positions = |word| hyphenate_positions(word, English).collect::<Vec<_>>();
+        assert_eq!(positions(""), []);
+        assert_eq!(positions("hi"), []);
+        assert_eq!(positions("hyphenation"), [2, 6]);
+        assert_eq!(hyphenate_positions("hyphenation", English).len(), 2);
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "german"))]
+    fn test_ranges() {
+        for (word, lang) in [
+            ("hyphenation", English),
+            ("hi", English),
+            ("(well-being),", English),
+            ("Größenmaßstäbe", German),
+        ] {
+            let ranges: Vec<_> = hyphenate_ranges(word, lang).collect();
+            assert_eq!(ranges.len(), hyphenate_ranges(word, lang).len());
+            assert_eq!(ranges.first().unwrap().start, 0);
+            assert_eq!(ranges.last().unwrap().end, word.len());
+            assert!(ranges.windows(2).all(|pair| pair[0].end == pair[1].start));
+
+            let syllables: Vec<_> = ranges.into_iter().map(|r| &word[r]).collect();
+            assert_eq!(syllables.concat(), word);
+            assert_eq!(syllables, hyphenate(word, lang).collect::<Vec<_>>());
+        }
+        assert_eq!(hyphenate_ranges("", English).next(), None);
+        assert_eq!(hyphenate_ranges("", English).len(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_hinted() {
+        let hinted = |word, hints| {
+            hyphenate_hinted(word, English, hints).collect::<Vec<_>>().join("-")
+        };
+        assert_eq!(hinted("hyphenation", Hints::Add), "hy-phen-ation");
+        assert_eq!(hinted("hyphenation", Hints::Only), "hyphenation");
+        assert_eq!(hinted("hyphen\u{ad}ation", Hints::Add), "hy-phen-ation");
+        assert_eq!(hinted("hyp\u{ad}henation", Hints::Add), "hy-p-hen-ation");
+        assert_eq!(hinted("hyp\u{ad}henation", Hints::Only), "hyp-henation");
+
+        // Hints break within the bounds and never end up in a syllable.
+        assert_eq!(hinted("e\u{ad}xtensive", Hints::Add), "e-x-ten-sive");
+        assert_eq!(hinted("\u{ad}ex\u{ad}\u{ad}ten\u{ad}", Hints::Only), "ex-ten");
+        assert_eq!(hinted("\u{ad}", Hints::Add), "");
+        assert_eq!(hinted("", Hints::Add), "");
+
+        let soft = hyphenate_soft("Probability", English);
+        assert_eq!(
+            hinted(&soft, Hints::Only),
+            hyphenate("Probability", English).join("-")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_matching_patterns() {
+        let patterns = matching_patterns("Hyphenation", English);
+        assert_eq!(
+            patterns,
+            ["hy3ph", "he2n", "hena4", "hen5at", "1na", "n2at", "1tio", "2io", "o2n"]
+        );
+        assert_eq!(matching_patterns("associate", English), ["as-so-ciate"]);
+        assert!(matching_patterns("", English).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_hyphenate_iter() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let tokens: Vec<&str> = "extensive hi  wonderful".split(' ').collect();
+        let iter = tokens.iter().hyphenate(English);
+        assert_send_sync(&iter);
+        assert_eq!(iter.len(), 4);
+        let hyphenated: Vec<_> = iter.map(|syllables| syllables.join("-")).collect();
+        assert_eq!(hyphenated, ["ex-ten-sive", "hi", "", "won-der-ful"]);
+
+        let owned: Vec<String> = ["Probability"].map(String::from).into();
+        let mut iter = owned.iter().hyphenate(English);
+        assert_eq!(iter.next().unwrap().join("-"), "Prob-a-bil-ity");
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "german"))]
+    fn test_best_break() {
+        assert_eq!(hyphenate_best_break("extensive", English), Some(5));
+        assert_eq!(hyphenate_best_break("hyphenation", English), Some(6));
+        assert_eq!(hyphenate_best_break("Probability", English), Some(5));
+        assert_eq!(hyphenate_best_break("häßlicher", German), Some(7));
+        assert_eq!(hyphenate_best_break("hello", English), None);
+        assert_eq!(hyphenate_best_break("", English), None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "german"))]
+    fn test_max_breaks() {
+        let test = |word: &str, lang: Lang, max, hyphenated: &str| {
+            assert_eq!(hyphenate_max_breaks(word, lang, max).join("-"), hyphenated);
+        };
+
+        for word in ["extensive", "hyphenation", "Probability", "uncharacteristically"] {
+            let best = hyphenate_best_break(word, English);
+            let mut positions = hyphenate_max_breaks(word, English, 1).into_positions();
+            assert_eq!(positions.next(), best);
+            assert_eq!(positions.next(), None);
+            test(word, English, 0, word);
+            test(word, English, 100, &hyphenate(word, English).join("-"));
+        }
+
+        test("uncharacteristically", English, 2, "uncharac-ter-istically");
+        test("uncharacteristically", English, 3, "uncharac-ter-is-tically");
+        test("häßlicher", German, 1, "häßli-cher");
+        test("well-being", English, 1, "well-be-ing");
+        test("well-being", English, 0, "well-being");
+        test("", English, 1, "");
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_classified() {
+        let classified = |word, is_letter: fn(char) -> bool| {
+            hyphenate_classified(word, English, is_letter).join("-")
+        };
+        assert_eq!(classified("my_variable_name", is_letter), "my_vari-able_name");
+        assert_eq!(
+            classified("my_variable_name", |c| c == '_' || is_letter(c)),
+            "my_-vari-able_-name"
+        );
+        assert_eq!(classified("html5elements", is_letter), "html5el-e-ments");
+        assert_eq!(
+            classified("html5elements", |c| c.is_ascii_digit() || is_letter(c)),
+            "htm-l5ele-ments"
+        );
+        assert_eq!(classified("extensive", |_| false), "extensive");
+        assert_eq!(hyphenate("my_variable_name", English).join("-"), "my_vari-able_name");
+        assert_eq!(hyphenate("html5elements", English).join("-"), "html5el-e-ments");
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "russian"))]
+    fn test_mixed() {
+        let test = |word, lang, hyphenated| {
+            assert_eq!(hyphenate_mixed(word, lang).join("-"), hyphenated);
+        };
+
+        // No break at the transition between the scripts.
+        assert_eq!(hyphenate("wonderfulмолоко", Russian).join("-"), "wonderful-мо-ло-ко");
+        test("wonderfulмолоко", Russian, "won-der-fulмо-ло-ко");
+        test("wonderfulмолоко", English, "won-der-fulмо-ло-ко");
+        test("Яндексmarket", English, "Ян-дексmar-ket");
+        test("iPhoneовский", Russian, "iPhoneов-ский");
+
+        // Words in a single script are hyphenated as usual.
+        test("extensive", English, "ex-ten-sive");
+        test("молоко", Russian, "мо-ло-ко");
+        test("well-being", English, "well-be-ing");
+        test("", English, "");
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "greek", feature = "afrikaans"))]
+    fn test_default_bounds() {
+        assert_eq!(English.bounds(), (2, 3));
+        assert_eq!(Greek.bounds(), (1, 1));
+        assert_eq!(Afrikaans.bounds(), (1, 2));
+        assert!(Lang::iter().all(|lang| lang.bounds().0 >= 1 && lang.bounds().1 >= 1));
+
+        // The bounds are what `hyphenate` uses by default.
+        for word in ["extensive", "abandoned", "intended", "eraser"] {
+            let default = hyphenate(word, English).join("-");
+            assert_eq!(hyphenate_bounded(word, English, 2, 3).join("-"), default);
+        }
+        assert_eq!(hyphenate("eraser", English).join("-"), "eraser");
+        assert_eq!(hyphenate_bounded("eraser", English, 1, 1).join("-"), "eras-er");
+    }
+
+    #[test]
+    fn test_script_of() {
+        assert_eq!(Script::of('a'), Some(Script::Latin));
+        assert_eq!(Script::of('É'), Some(Script::Latin));
+        assert_eq!(Script::of('ж'), Some(Script::Cyrillic));
+        assert_eq!(Script::of('λ'), Some(Script::Greek));
+        assert_eq!(Script::of('ა'), Some(Script::Georgian));
+        for c in ['1', '\'', '-', '×', 'א'] {
+            assert_eq!(Script::of(c), None);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_exceptions_override() {
+        let mut exceptions = Exceptions::new();
+        assert!(exceptions.is_empty());
+        assert_eq!(hyphenate("project", English).join("-"), "project");
+        exceptions.insert("project", &[4]);
+        exceptions.insert("extensive", &[0, 5, 2, 5, 9, 100]);
+        exceptions.insert("naïve", &[3]);
+        assert_eq!(exceptions.len(), 3);
+
+        let test = |exceptions: &Exceptions, word, hyphenated| {
+            assert_eq!(exceptions.hyphenate(word, English).join("-"), hyphenated);
+        };
+
+        test(&exceptions, "project", "proj-ect");
+        test(&exceptions, "Project", "Proj-ect");
+        test(&exceptions, "PROJECT", "PROJ-ECT");
+        test(&exceptions, "extensive", "ex-ten-sive");
+        test(&exceptions, "naïve", "naïve");
+        test(&exceptions, "projects", "projects");
+        test(&exceptions, "wonderful", "won-der-ful");
+        assert_eq!(exceptions.get("extensive"), Some(vec![2, 5]));
+
+        exceptions.insert("project", &[]);
+        test(&exceptions, "project", "project");
+        assert_eq!(exceptions.remove("Project"), Some(vec![]));
+        assert_eq!(exceptions.remove("project"), None);
+        assert_eq!(exceptions.len(), 2);
+
+        // The capital I with dot above is longer in UTF-8 than `i`.
+        exceptions.insert("İstanbul", &[3, 6]);
+        test(&exceptions, "istanbul", "is-tan-bul");
+        test(&exceptions, "İSTANBUL", "İS-TAN-BUL");
+        assert_eq!(exceptions.get("istanbul"), Some(vec![2, 5]));
+        assert_eq!(exceptions.get("İstanbul"), Some(vec![3, 6]));
+        exceptions.insert("don’t", &[3]);
+        test(&exceptions, "don't", "don-'t");
+        assert_eq!(exceptions.remove("DON’T"), Some(vec![3]));
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "german", feature = "russian"))]
+    fn test_bytes() {
+        let test = |word: &str, lang| {
+            let ranges: Vec<_> = hyphenate_bytes(word.as_bytes(), lang).collect();
+            assert_eq!(ranges, hyphenate_ranges(word, lang).collect::<Vec<_>>());
+        };
+
+        for word in ["", "a", "extensive", "Hyphenation", "well-being", "it's"] {
+            test(word, English);
+        }
+        test("häßlicher", German);
+        test("Schifffahrt", German);
+        test("молоко", Russian);
+        test("супер-маркет", Russian);
+
+        // Invalid sequences are separators and never broken.
+        let ranges = |word: &[u8]| hyphenate_bytes(word, English).collect::<Vec<_>>();
+        assert_eq!(ranges(b"\xffextensive"), [0..3, 3..6, 6..10]);
+        assert_eq!(ranges(b"exten\xe2\x80sive"), [0..2, 2..11]);
+        assert_eq!(ranges(b"extensive\xf0\x9f"), [0..2, 2..5, 5..11]);
+        assert_eq!(hyphenate_bytes(b"\xff\xfe", English).next(), Some(0..2));
+        assert_eq!(hyphenate_bytes(b"\xffextensive", English).len(), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_string() {
+        for word in ["", "hi", "extensive", "Probability", "well-being", "wHaT-eVeR"] {
+            let joined = hyphenate(word, English).join("-");
+            assert_eq!(hyphenate_string(word, English, '-'), joined);
+        }
+        assert_eq!(hyphenate_string("extensive", English, '\u{2027}'), "ex‧ten‧sive");
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_soft() {
+        let soft = hyphenate_soft("Probability", English);
+        assert_eq!(soft.matches('\u{ad}').count(), 3);
+        assert_eq!(soft.replace('\u{ad}', ""), "Probability");
+        assert_eq!(hyphenate_soft("hi", English), "hi");
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_min_len() {
+        let min_len = |word, min_len| hyphenate_min_len(word, English, min_len).join("-");
+        assert_eq!(min_len("extensive", 9), "ex-ten-sive");
+        assert_eq!(min_len("extensive", 10), "extensive");
+
+        // Chars are counted, not bytes.
+        let word = "naïveté-ridden";
+        let full = hyphenate(word, English).join("-");
+        assert_ne!(full, word);
+        assert_eq!(min_len(word, 14), full);
+        assert_eq!(min_len(word, 15), word);
+
+        assert_eq!(min_len("", 0), "");
+        assert_eq!(min_len("", 1), "");
+        assert_eq!(min_len("hyphenation", 0), "hy-phen-ation");
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "french"))]
+    fn test_fallback() {
+        let fallback = |word, langs: &[Lang]| hyphenate_fallback(word, langs).join("-");
+        assert_eq!(hyphenate("playing", French).join("-"), "playing");
+        assert_eq!(fallback("playing", &[French, English]), "play-ing");
+        assert_eq!(fallback("hyphenation", &[French, English]), "hy-phe-na-tion");
+        assert_eq!(fallback("hyphenation", &[English, French]), "hy-phen-ation");
+        assert_eq!(fallback("rhythm", &[French, English]), "rhythm");
+        assert_eq!(fallback("playing", &[]), "playing");
+        assert_eq!(hyphenate_fallback("", &[French, English]).len(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_cow() {
+        use std::borrow::Cow;
+        assert!(matches!(hyphenate_cow("of", English, "-"), Cow::Borrowed("of")));
+        assert!(matches!(hyphenate_cow("the", English, "-"), Cow::Borrowed("the")));
+        assert!(matches!(hyphenate_cow("", English, "-"), Cow::Borrowed("")));
+        match hyphenate_cow("hyphenation", English, "\u{ad}") {
+            Cow::Owned(s) => assert_eq!(s, "hy\u{ad}phen\u{ad}ation"),
+            Cow::Borrowed(s) => panic!("expected owned string, got {s:?}"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "german")]
+    fn test_positions_multibyte() {
+        let word = "häßlicher";
+        let positions: Vec<_> = hyphenate_positions(word, German).collect();
+        assert_eq!(positions, [5, 7]);
+        assert!(positions.iter().all(|&i| word.is_char_boundary(i)));
+    }
+
+    #[test]
+    #[cfg(feature = "german")]
+    fn test_char_positions() {
+        let chars = |word| hyphenate_char_positions(word, German).collect::<Vec<_>>();
+        let bytes = |word| hyphenate_positions(word, German).collect::<Vec<_>>();
+
+        assert_eq!(chars("Eingabeaufforderung"), bytes("Eingabeaufforderung"));
+        assert_eq!(chars("Größenmaßstäbe"), [3, 6, 9, 12]);
+        assert_eq!(bytes("Größenmaßstäbe"), [4, 8, 12, 16]);
+        assert_eq!(hyphenate_char_positions("Größenmaßstäbe", German).len(), 4);
+
+        let word = "Äußerung";
+        let all: Vec<char> = word.chars().collect();
+        let split: Vec<String> = chars(word)
+            .into_iter()
+            .chain([all.len()])
+            .scan(0, |start, end| {
+                let syllable = all[*start..end].iter().collect();
+                *start = end;
+                Some(syllable)
+            })
+            .collect();
+        assert_eq!(split, hyphenate(word, German).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_explain() {
+        let tex = r"\patterns{ .a1 a2b b3c c4. 5ä ä6c }";
+        let trie = crate::builder::build_trie(tex).unwrap();
+        let explain = |word| super::explain_from(word, State::root(&trie).unwrap());
+
+        // Dotted: . a b c .
+        // .a1:       1
+        // a2b:       2
+        // b3c:         3
+        // c4.:           4
+        assert_eq!(explain("abc"), [0, 2, 3, 4]);
+        assert_eq!(explain("ABC"), [0, 2, 3, 4]);
+        assert_eq!(explain("ab-c"), [0, 2, 0, 0, 4]);
+        assert_eq!(explain("bÄc"), [0, 5, 0, 6, 4]);
+        assert_eq!(explain(""), [0]);
+    }
+
+    #[test]
+    fn test_unusual_words() {
+        let long = "hyphenation".repeat(1000);
+        let combining = "e\u{301}\u{301}\u{301}".repeat(20);
+        let words = ["", "\u{301}", "\u{301}\u{302}", "'", "’’", "-", "\0", "\u{10FFFF}"];
+        for lang in Lang::all().iter().copied() {
+            for word in words.iter().copied().chain([long.as_str(), combining.as_str()]) {
+                assert_eq!(hyphenate(word, lang).collect::<String>(), word);
+                let syllables = hyphenate_bounded(word, lang, usize::MAX, usize::MAX);
+                assert_eq!(syllables.count(), usize::from(!word.is_empty()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert_eq!(Trie::from_bytes(b"garbage, not a trie"), Err(TrieError::NotATrie));
+        let data = b"HYPH\x02\0\0\x02\x03\xff\xff\xff\xffgarbage";
+        assert_eq!(Trie::from_bytes(data), Err(TrieError::Malformed));
+
+        // Truncating or corrupting the trie may yield nonsense, but no panics.
+        let tex = std::fs::read_to_string("tests/fixtures/dump.tex").unwrap();
+        let trie = crate::builder::build_trie(&tex).unwrap();
+        let check = |data: &[u8]| {
+            if let Ok(trie) = Trie::from_bytes(data) {
+                for word in ["wonderful", "extensive", "Won-der", "ondo"] {
+                    trie.hyphenate(word).for_each(drop);
+                    super::patterns_from(word, trie.root());
+                }
+                let _ = crate::builder::trie_stats(data);
+                let _ = crate::builder::dump_trie(data);
+                let _ = crate::builder::TrieBuilder::new().verify(data);
+            }
+        };
+
+        // A root whose only transition leads back to itself.
+        let cyclic = b"HYPH\x02\0\0\x02\x03\0\0\0\x04\x01a\0";
+        check(cyclic);
+        assert_eq!(crate::builder::dump_trie(cyclic), Err(TrieError::Malformed));
+        assert!(crate::builder::TrieBuilder::new().verify(cyclic).is_err());
+
+        for len in 0..trie.len() {
+            check(&trie[..len]);
+        }
+
+        // The root node comes last, so truncated tries are rejected upfront.
+        #[cfg(feature = "english")]
+        {
+            let data = English.trie().as_bytes();
+            for len in (0..data.len()).step_by(7) {
+                assert!(Trie::from_bytes(&data[..len]).is_err());
+            }
+        }
+
+        for i in HEADER_LEN..trie.len() {
+            for b in [0x00, 0x01, 0x1F, 0x7F, 0x80, 0xFF] {
+                let mut corrupted = trie.clone();
+                corrupted[i] = b;
+                check(&corrupted);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "german", feature = "russian"))]
+    fn test_char_boundaries() {
+        test(German, "Grö-ßen-maß-stä-be");
+        test(German, "Äu-ße-rung");
+        test(Russian, "до-сто-при-ме-ча-тель-ность");
+        test(Russian, "ВЫ-ЧИС-ЛИ-ТЕЛЬ-НЫЙ");
+
+        let words = ["Größenmaßstäbe", "Äußerung", "сумасшедший", "Пётр"];
+        for (word, lang) in words.into_iter().zip([German, German, Russian, Russian]) {
+            let positions: Vec<_> = hyphenate_positions(word, lang).collect();
+            assert!(positions.iter().all(|&i| word.is_char_boundary(i)));
+        }
+    }
+
     #[test]
     #[cfg(feature = "german")]
     fn test_german() {
@@ -568,4 +3191,184 @@ mod tests {
         test(Czech, "nej-ja-s-něj-ší");
         test(Czech, "br-něn-ský");
     }
+
+    #[test]
+    #[cfg(feature = "full")]
+    fn test_iter() {
+        assert_eq!(Lang::all().len(), 35);
+        assert_eq!(Lang::iter().next(), Some(Lang::Afrikaans));
+        for lang in Lang::iter() {
+            assert_eq!(Lang::from_iso(lang.iso().unwrap()), Some(lang));
+        }
+        assert_eq!(Lang::from_iso(*b"nb").unwrap().iso(), Some(*b"no"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", not(feature = "german")))]
+    fn test_disabled_language() {
+        // Languages whose feature is disabled don't exist at all.
+        assert_eq!(Lang::from_iso(*b"en"), Some(English));
+        assert_eq!(Lang::from_iso(*b"de"), None);
+        assert_eq!(Lang::from_name("German"), None);
+        assert_eq!("de".parse::<Lang>(), Err(ParseLangError::UnknownLanguage));
+        assert!(Lang::iter().all(|lang| lang.iso() != Some(*b"de")));
+        assert_eq!(Lang::iter().count(), Lang::all().len());
+    }
+
+    #[test]
+    #[cfg(feature = "full")]
+    fn test_script() {
+        assert_eq!(English.script(), Some(Script::Latin));
+        assert_eq!(Ukrainian.script(), Some(Script::Cyrillic));
+        assert_eq!(Greek.script(), Some(Script::Greek));
+        assert_eq!(Georgian.script(), Some(Script::Georgian));
+        assert_eq!(Serbian.script().unwrap().code(), *b"Cyrl");
+
+        assert_eq!(Lang::for_script(Script::Latin), Some(English));
+        assert_eq!(Lang::for_script(Script::Cyrillic), Some(Russian));
+        for script in [Script::Latin, Script::Cyrillic, Script::Greek, Script::Georgian] {
+            assert_eq!(Lang::for_script(script).unwrap().script(), Some(script));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_parse() {
+        assert_eq!("en".parse::<Lang>(), Ok(English));
+        assert_eq!(English.to_string(), "en");
+        assert_eq!("e".parse::<Lang>(), Err(ParseLangError::InvalidCode));
+        assert_eq!("eng".parse::<Lang>(), Err(ParseLangError::InvalidCode));
+        assert_eq!("EN".parse::<Lang>(), Err(ParseLangError::UnknownLanguage));
+        assert_eq!("xx".parse::<Lang>(), Err(ParseLangError::UnknownLanguage));
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "french", feature = "german"))]
+    fn test_from_name() {
+        assert_eq!(Lang::from_name("English"), Some(English));
+        assert_eq!(Lang::from_name("english"), Some(English));
+        assert_eq!(Lang::from_name("français"), Some(French));
+        assert_eq!(Lang::from_name("FRANÇAIS"), Some(French));
+        assert_eq!(Lang::from_name("German"), Some(German));
+        assert_eq!(Lang::from_name("Deutsch"), Some(German));
+        assert_eq!(Lang::from_name("Klingon"), None);
+        assert_eq!(Lang::from_name("en"), None);
+        assert_eq!(German.name(), Some("German"));
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_bounded() {
+        let bounded = |left_min, right_min| {
+            hyphenate_bounded("hyphenation", English, left_min, right_min).join("-")
+        };
+        assert_eq!(bounded(1, 1), "hy-phen-ation");
+        assert_eq!(bounded(2, 3), "hy-phen-ation");
+        assert_eq!(bounded(3, 3), "hyphen-ation");
+        assert_eq!(bounded(2, 6), "hy-phenation");
+        assert_eq!(bounded(7, 1), "hyphenation");
+        assert_eq!(bounded(6, 6), "hyphenation");
+        assert_eq!(bounded(usize::MAX, usize::MAX), "hyphenation");
+        assert_eq!(hyphenate_bounded("", English, 5, 5).join("-"), "");
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "french"))]
+    fn test_punctuation() {
+        let test = |lang, hyphenated: &str| {
+            let word = hyphenated.replace('|', "");
+            assert_eq!(hyphenate(&word, lang).join("|"), hyphenated);
+        };
+        test(English, "don't");
+        test(English, "don’t");
+        test(English, "well-be|ing");
+        test(English, "self-ex|plana|tory");
+        test(English, "hello,");
+        test(English, "(hy|phen|ation),");
+        test(English, "“won|der|ful”");
+        test(English, "--");
+        test(French, "au|jour|d'hui");
+        test(French, "au|jour|d’hui");
+        assert_eq!(hyphenate_positions("well-being", English).collect::<Vec<_>>(), [7]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "german"))]
+    fn test_compounds() {
+        let test = |lang, hyphenated: &str| {
+            let word = hyphenated.replace('|', "");
+            assert_eq!(hyphenate(&word, lang).join("|"), hyphenated);
+            assert!(!hyphenate(&word, lang).join("-").contains("--"));
+        };
+        test(English, "mother-in-law");
+        test(English, "self-ex|plana|tory");
+        test(English, "hy|phen|ation-based");
+        test(German, "Mut|ter-Kind-Be|zie|hung");
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "serde"))]
+    fn test_serde() {
+        use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+        assert_tokens(&English, &[Token::Str("en")]);
+        assert_de_tokens_error::<Lang>(
+            &[Token::Str("xx")],
+            "no language with this code is available",
+        );
+
+        let hyphenated = Hyphenated::from(hyphenate("hyphenation", English));
+        assert_tokens(
+            &hyphenated,
+            &[
+                Token::Struct { name: "Hyphenated", len: 1 },
+                Token::Str("syllables"),
+                Token::Seq { len: Some(3) },
+                Token::Str("hy"),
+                Token::Str("phen"),
+                Token::Str("ation"),
+                Token::SeqEnd,
+                Token::StructEnd,
+            ],
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "rayon"))]
+    fn test_parallel() {
+        fn assert_sync<T: Send + Sync>() {}
+        assert_sync::<Lang>();
+
+        let text = include_str!("../tests/fixtures/batch.txt").repeat(100);
+        let words: Vec<_> = text.split_whitespace().collect();
+        let sequential: Vec<Vec<_>> =
+            words.iter().map(|word| hyphenate(word, English).collect()).collect();
+        assert_eq!(super::hyphenate_all(&words, English), sequential);
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_threads() {
+        fn assert_sync<T: Send + Sync>() {}
+        assert_sync::<Trie>();
+        assert_sync::<Lang>();
+
+        let text = include_str!("../tests/fixtures/batch.txt");
+        let expected: Vec<String> = text
+            .split_whitespace()
+            .map(|word| hyphenate(word, English).join("-"))
+            .collect();
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..200 {
+                        let hyphenated = text
+                            .split_whitespace()
+                            .map(|word| hyphenate(word, English).join("-"));
+                        assert!(hyphenated.eq(expected.iter().cloned()));
+                    }
+                });
+            }
+        });
+    }
 }
@@ -1,13 +1,18 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use hypher::builder::{Compression, TrieBuilder};
+use hypher::{Lang, Trie};
+use std::collections::HashSet;
 use std::error::Error;
 use std::fs;
+use std::io::{self, BufRead, Read, Write};
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
 #[derive(Parser)]
-#[clap(name = "hypher", version)]
+#[clap(name = "hypher", version, arg_required_else_help = true)]
 pub struct Cli {
     #[command(subcommand)]
-    command: Option<Command>,
+    command: Command,
 }
 
 #[derive(Subcommand)]
@@ -15,68 +20,653 @@ enum Command {
     /// Builds a trie from a pattern file.
     Build {
         /// Input file to read the patterns from.
+        /// With the `compress` feature, `.gz` and `.zst` files are
+        /// decompressed first. Files included with `\input` are resolved
+        /// relative to the including file. With `-`, the patterns are read
+        /// from stdin instead and `\input` is ignored.
         file: PathBuf,
-        /// Destination file to write the trie to.
+        /// Destination file to write the trie to, or `-` for stdout.
         dest: PathBuf,
+        /// Character encoding of the input file.
+        /// Latin-1 files are converted to UTF-8 before building, so that the
+        /// trie matches UTF-8 words like any other. They are read as is,
+        /// without decompression, and `\input` is ignored.
+        #[arg(
+            long = "encoding",
+            value_enum,
+            value_name = "CHARSET",
+            default_value = "utf8"
+        )]
+        charset: Charset,
+        #[command(flatten)]
+        encoding: Encoding,
+        /// Add the patterns to those of an existing trie at the destination.
+        /// New patterns take precedence over existing ones with the same
+        /// letters.
+        #[arg(long)]
+        append: bool,
+        /// Print statistics about the patterns and the trie to stderr.
+        #[arg(long)]
+        stats: bool,
+        /// Decode the trie again and check that it holds exactly the patterns
+        /// and exceptions before writing it.
+        #[arg(long)]
+        verify: bool,
+    },
+    /// Prints the patterns and exceptions stored in a trie.
+    Dump {
+        /// Trie file to decompile.
+        trie: PathBuf,
+    },
+    /// Prints statistics about a trie.
+    Info {
+        /// Trie file to inspect.
+        trie: PathBuf,
+    },
+    /// Checks a pattern file for common problems.
+    ///
+    /// Reports patterns without levels, duplicate patterns and exceptions,
+    /// patterns that replace others with different levels, patterns that
+    /// can't be encoded and files without patterns, including those in files
+    /// included with `\input`. Exits with an error if there are any.
+    Doctor {
+        /// Pattern file to check.
+        file: PathBuf,
     },
     /// Splits a word into syllables.
     Query {
-        /// Optional language to use.
-        /// If this is not specified, then `--trie` MUST be given instead.
-        #[arg(long, value_name = "ISO")]
-        lang: Option<String>,
-        /// Optional pattern file to use.
-        /// If this is not specifed, then `--lang` MUST be given instead.
-        #[arg(long, value_name = "BIN")]
-        trie: Option<PathBuf>,
-        /// Word to segment into syllables.
-        word: String,
+        #[command(flatten)]
+        select: Select,
+        #[command(flatten)]
+        format: Format,
+        /// Print the number of possible breaks of each word instead of its
+        /// syllables.
+        #[arg(long, conflicts_with_all = ["sep", "soft"])]
+        count: bool,
+        /// Print each word as a JSON object with its syllables and the byte
+        /// offsets of the breaks between them. Overrides `--sep` and
+        /// `--soft`.
+        #[arg(long, conflicts_with = "count")]
+        json: bool,
+        /// Words to segment into syllables, each printed on its own line.
+        /// If none are specified, each line read from stdin is segmented
+        /// independently and printed on its own line, so that an empty stdin
+        /// produces no output.
+        words: Vec<String>,
     },
+    /// Splits all words of a text file into syllables.
+    Batch {
+        #[command(flatten)]
+        select: Select,
+        #[command(flatten)]
+        format: Format,
+        /// Input file to read the text from, or `-` for stdin.
+        file: PathBuf,
+        /// Optional destination file to write the hyphenated text to.
+        /// If this is not specified or `-`, the text is written to stdout.
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+    /// Inserts soft hyphens (U+00AD) into all words of a text read from
+    /// stdin.
+    ///
+    /// The text is written to stdout with everything but the runs of letters
+    /// left byte-for-byte identical.
+    Fmt {
+        #[command(flatten)]
+        select: Select,
+    },
+}
+
+/// Selects the patterns to hyphenate with.
+#[derive(Args)]
+struct Select {
+    /// Optional language to use, given by its ISO 639-1, 639-2 or 639-3 code
+    /// or its name, ignoring case.
+    /// If this is not specified, then `--trie` MUST be given instead.
+    /// With `query`, this can be repeated to compare languages: each word is
+    /// then printed once per language, prefixed by the code. With `--json`,
+    /// each word is instead printed as one object with the results keyed by
+    /// the codes.
+    #[arg(long, value_name = "ISO")]
+    lang: Vec<String>,
+    /// Optional pattern file to use.
+    /// If this is not specifed, then `--lang` MUST be given instead.
+    #[arg(long, value_name = "BIN")]
+    trie: Option<PathBuf>,
+}
+
+impl Select {
+    /// Load the selected language, reading a trie file into `data` if needed.
+    fn load<'a>(
+        &self,
+        data: &'a mut Option<TrieData>,
+    ) -> Result<Lang<'a>, Box<dyn Error>> {
+        match self.load_all(data)?.as_slice() {
+            [(_, lang)] => Ok(*lang),
+            _ => Err("`--lang` can only be given once for this command".into()),
+        }
+    }
+
+    /// Load all selected languages together with the codes they were given
+    /// by, reading a trie file into `data` if needed.
+    fn load_all<'a>(
+        &self,
+        data: &'a mut Option<TrieData>,
+    ) -> Result<Vec<(&str, Lang<'a>)>, Box<dyn Error>> {
+        match (self.lang.as_slice(), &self.trie) {
+            ([], Some(file)) => {
+                let data = data.insert(read_trie(file)?);
+                Ok(vec![("trie", Lang::from_bytes(data)?)])
+            }
+            ([_, ..], None) => self
+                .lang
+                .iter()
+                .map(|code| match parse_lang(code) {
+                    Some(lang) => Ok((code.as_str(), lang)),
+                    None => Err(format!(
+                        "--lang={code} is neither an ISO code nor a language name"
+                    )
+                    .into()),
+                })
+                .collect(),
+            _ => Err("must specify exactly one of `--lang` or `--trie`".into()),
+        }
+    }
+}
+
+/// Parse a language from its ISO 639-1, 639-2 or 639-3 code or its name,
+/// ignoring case.
+fn parse_lang(code: &str) -> Option<Lang<'static>> {
+    let lower = code.to_lowercase();
+    let iso = ISO_639_2
+        .iter()
+        .chain(&ISO_639_3)
+        .find(|(long, _)| *long == lower)
+        .map_or(lower.as_str(), |&(_, short)| short);
+    iso.parse().ok().or_else(|| Lang::from_name(code))
+}
+
+/// The ISO 639-2 codes of the supported languages and their ISO 639-1
+/// equivalents. Both the bibliographic and terminological codes are listed.
+const ISO_639_2: [(&str, &str); 46] = [
+    ("afr", "af"),
+    ("alb", "sq"),
+    ("bel", "be"),
+    ("bul", "bg"),
+    ("cat", "ca"),
+    ("ces", "cs"),
+    ("cze", "cs"),
+    ("dan", "da"),
+    ("deu", "de"),
+    ("dut", "nl"),
+    ("ell", "el"),
+    ("eng", "en"),
+    ("est", "et"),
+    ("fin", "fi"),
+    ("fra", "fr"),
+    ("fre", "fr"),
+    ("geo", "ka"),
+    ("ger", "de"),
+    ("gre", "el"),
+    ("hrv", "hr"),
+    ("hun", "hu"),
+    ("ice", "is"),
+    ("isl", "is"),
+    ("ita", "it"),
+    ("kat", "ka"),
+    ("kur", "ku"),
+    ("lat", "la"),
+    ("lit", "lt"),
+    ("mon", "mn"),
+    ("nld", "nl"),
+    ("nno", "nn"),
+    ("nob", "nb"),
+    ("nor", "no"),
+    ("pol", "pl"),
+    ("por", "pt"),
+    ("rus", "ru"),
+    ("slk", "sk"),
+    ("slo", "sk"),
+    ("slv", "sl"),
+    ("spa", "es"),
+    ("sqi", "sq"),
+    ("srp", "sr"),
+    ("swe", "sv"),
+    ("tuk", "tk"),
+    ("tur", "tr"),
+    ("ukr", "uk"),
+];
+
+/// ISO 639-3 codes of supported languages without an ISO 639-2 code of their
+/// own and their ISO 639-1 equivalents.
+const ISO_639_3: [(&str, &str); 1] = [
+    // The Kurdish patterns are for Kurmanji.
+    ("kmr", "ku"),
+];
+
+/// The contents of a trie file.
+#[cfg(not(feature = "mmap"))]
+type TrieData = Vec<u8>;
+
+/// The contents of a trie file, mapped into memory.
+#[cfg(feature = "mmap")]
+type TrieData = memmap2::Mmap;
+
+/// Read a trie file into memory.
+#[cfg(not(feature = "mmap"))]
+fn read_trie(path: &Path) -> io::Result<TrieData> {
+    fs::read(path)
+}
+
+/// Map a trie file into memory instead of copying it.
+#[cfg(feature = "mmap")]
+fn read_trie(path: &Path) -> io::Result<TrieData> {
+    let file = fs::File::open(path)?;
+    // SAFETY: The map is only ever read. If another process modifies or
+    // truncates the file while we're using it, the trie may appear corrupted or
+    // the process may be killed by a bus error. That's acceptable for a command
+    // line tool that exits right after, but not something a library could
+    // expose.
+    unsafe { memmap2::Mmap::map(&file) }
+}
+
+/// The character encoding of a pattern file.
+#[derive(Clone, Copy, ValueEnum)]
+enum Charset {
+    /// UTF-8.
+    Utf8,
+    /// ISO 8859-1, used by older pattern files for Western European languages.
+    Latin1,
+}
+
+/// How to encode a built trie.
+#[derive(Args)]
+struct Encoding {
+    /// Append a checksum to detect corrupted tries.
+    #[arg(long)]
+    checksum: bool,
+    /// Store a short text like the language and source of the patterns in
+    /// the trie.
+    #[arg(long)]
+    metadata: Option<String>,
+    /// Skip suffix compression, so that the nodes are encoded in the order in
+    /// which they were inserted. This makes the trie larger, but easier to
+    /// debug.
+    #[arg(long)]
+    no_compress: bool,
+}
+
+impl Encoding {
+    /// Compress the trie unless disabled and encode it.
+    fn encode(
+        &self,
+        builder: &mut TrieBuilder,
+    ) -> Result<(Compression, Vec<u8>), Box<dyn Error>> {
+        builder.set_checksum(self.checksum);
+        builder.set_metadata(self.metadata.as_deref().unwrap_or_default());
+        let compression = if self.no_compress {
+            let nodes = builder.node_count();
+            Compression { before: nodes, after: nodes }
+        } else {
+            builder.compress()
+        };
+        Ok((compression, builder.encode()?))
+    }
+}
+
+/// How to print hyphenated words.
+#[derive(Args)]
+struct Format {
+    /// Separator to print between the syllables.
+    #[arg(long, value_name = "STRING", default_value = "-", conflicts_with = "soft")]
+    sep: String,
+    /// Insert soft hyphens (U+00AD) instead of a separator.
+    #[arg(long)]
+    soft: bool,
+}
+
+impl Format {
+    /// Hyphenate a word for printing.
+    fn hyphenate(&self, word: &str, lang: Lang) -> String {
+        if self.soft {
+            hypher::hyphenate_soft(word, lang)
+        } else {
+            hypher::hyphenate(word, lang).join(&self.sep)
+        }
+    }
+}
+
+fn build_trie(
+    source: &Path,
+    dest: &Path,
+    charset: Charset,
+    encoding: &Encoding,
+    append: bool,
+    stats: bool,
+    verify: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut builder = TrieBuilder::new();
+    if append && !is_std(dest) && dest.exists() {
+        let existing = fs::read(dest)?;
+        builder.insert_tex(&hypher::builder::dump_trie(&existing)?)?;
+    }
+    if let Charset::Latin1 = charset {
+        builder.insert_tex(&decode_latin1(&read_bytes(source)?))?;
+    } else if is_std(source) {
+        builder.insert_tex(&read_input(source)?)?;
+    } else {
+        builder.insert_tex_file(source)?;
+    }
+    for conflict in builder.conflicts() {
+        eprintln!("warning: {conflict}");
+    }
+    let (compression, data) = encoding.encode(&mut builder)?;
+    if verify {
+        builder.verify(&data)?;
+    }
+    write_output(dest, &data)?;
+
+    if stats {
+        let trie = hypher::builder::trie_stats(&data)?;
+        eprintln!("patterns:       {}", builder.pattern_count());
+        eprintln!("exceptions:     {}", builder.exception_count());
+        eprintln!("nodes:          {} -> {}", compression.before, compression.after);
+        eprintln!("levels:         {} bytes", trie.levels);
+        eprintln!("size:           {} bytes", trie.size);
+    }
+
+    Ok(())
+}
+
+fn dump_trie(trie: &Path) -> Result<(), Box<dyn Error>> {
+    let data = fs::read(trie)?;
+    let tex = hypher::builder::dump_trie(&data)?;
+    io::stdout().lock().write_all(tex.as_bytes())?;
+    Ok(())
+}
+
+fn doctor(file: &Path) -> Result<(), Box<dyn Error>> {
+    // Look at the included files, too, like the builder below.
+    let mut patterns = vec![];
+    let mut exceptions = vec![];
+    for tex in hypher::builder::read_tex_files(file)? {
+        hypher::builder::parse(&tex, |pattern| patterns.push(pattern.to_string()));
+        hypher::builder::parse_exceptions(&tex, |word| exceptions.push(word.to_string()));
+    }
+
+    let mut problems = vec![];
+    if patterns.is_empty() {
+        problems.push("no patterns found, is there a `\\patterns{...}` block?".into());
+    }
+
+    let mut seen = HashSet::new();
+    for pattern in &patterns {
+        if !pattern.bytes().any(|b| matches!(b, b'1'..=b'9')) {
+            problems.push(format!("{pattern} has no levels"));
+        }
+        if !seen.insert(pattern) {
+            problems.push(format!("{pattern} is listed more than once"));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for word in &exceptions {
+        if !seen.insert(word) {
+            problems.push(format!("exception {word} is listed more than once"));
+        }
+    }
+
+    // Inserting and encoding checks the limits of the format.
+    let mut builder = TrieBuilder::new();
+    match builder.insert_tex_file(file) {
+        Ok(()) => {
+            for conflict in builder.conflicts() {
+                problems.push(conflict.to_string());
+            }
+            builder.compress();
+            if let Err(err) = builder.encode() {
+                problems.push(err.to_string());
+            }
+        }
+        Err(err) => problems.push(err.to_string()),
+    }
+
+    let mut stdout = io::stdout().lock();
+    for problem in &problems {
+        writeln!(stdout, "{problem}")?;
+    }
+    match problems.len() {
+        0 => Ok(writeln!(stdout, "no problems found")?),
+        1 => Err("found 1 problem".into()),
+        n => Err(format!("found {n} problems").into()),
+    }
 }
 
-fn build_trie(source: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
-    let tex = fs::read_to_string(&source)?;
-    let trie = hypher::builder::build_trie(&tex);
-    fs::write(dest, &trie)?;
+fn info(trie: &Path) -> Result<(), Box<dyn Error>> {
+    let data = fs::read(trie)?;
+    let stats = hypher::builder::trie_stats(&data)?;
+    println!("version:        {}", stats.version);
+    println!("size:           {} bytes", stats.size);
+    println!("nodes:          {}", stats.nodes);
+    println!("transitions:    {}", stats.transitions);
+    println!("levels:         {} bytes", stats.levels);
+    println!("lefthyphenmin:  {}", stats.left_min);
+    println!("righthyphenmin: {}", stats.right_min);
+    println!("checksum:       {}", if stats.checksum { "yes" } else { "no" });
+    let metadata = Trie::from_bytes(&data)?.metadata();
+    if !metadata.is_empty() {
+        println!("metadata:       {metadata}");
+    }
     Ok(())
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
+fn query(
+    select: &Select,
+    format: &Format,
+    count: bool,
+    json: bool,
+    words: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let mut data = None;
+    let langs = select.load_all(&mut data)?;
+    let render_with = |word: &str, lang| {
+        if count {
+            hypher::hyphenate_positions(word, lang).len().to_string()
+        } else if json {
+            to_json(word, lang)
+        } else {
+            format.hyphenate(word, lang)
+        }
+    };
+    let render = |word: &str| match langs.as_slice() {
+        [(_, lang)] => render_with(word, *lang),
+        _ if json => {
+            let results: Vec<_> = langs
+                .iter()
+                .map(|&(code, lang)| {
+                    format!("{}:{}", json_string(code), to_json(word, lang))
+                })
+                .collect();
+            format!("{{{}}}", results.join(","))
+        }
+        _ => langs
+            .iter()
+            .map(|&(code, lang)| format!("{code}: {}", render_with(word, lang)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+
+    let mut stdout = io::stdout().lock();
+    if words.is_empty() {
+        for (i, line) in io::stdin().lock().lines().enumerate() {
+            let line = line.map_err(|err| match err.kind() {
+                io::ErrorKind::InvalidData => {
+                    format!("line {} of stdin is not valid UTF-8", i + 1).into()
+                }
+                _ => Box::<dyn Error>::from(err),
+            })?;
+            writeln!(stdout, "{}", render(&line))?;
+        }
+    } else {
+        for word in words {
+            writeln!(stdout, "{}", render(word))?;
+        }
+    }
+    Ok(())
+}
+
+/// Render a word, its syllables and the byte offsets of its breaks as a JSON
+/// object.
+fn to_json(word: &str, lang: Lang) -> String {
+    let syllables: Vec<_> = hypher::hyphenate(word, lang).map(json_string).collect();
+    let positions: Vec<_> = hypher::hyphenate_positions(word, lang)
+        .map(|i| i.to_string())
+        .collect();
+    format!(
+        "{{\"word\":{},\"syllables\":[{}],\"positions\":[{}]}}",
+        json_string(word),
+        syllables.join(","),
+        positions.join(","),
+    )
+}
+
+/// Quote and escape a string for JSON.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn batch(
+    select: &Select,
+    format: &Format,
+    file: &Path,
+    out: Option<&Path>,
+) -> Result<(), Box<dyn Error>> {
+    let mut data = None;
+    let lang = select.load(&mut data)?;
+    let text = read_input(file)?;
+    let result = hyphenate_text(&text, lang, format);
+    write_output(out.unwrap_or(Path::new("-")), result.as_bytes())?;
+    Ok(())
+}
+
+fn fmt(select: &Select) -> Result<(), Box<dyn Error>> {
+    let mut data = None;
+    let lang = select.load(&mut data)?;
+    let mut text = String::new();
+    io::stdin().lock().read_to_string(&mut text)?;
+    let format = Format { sep: String::new(), soft: true };
+    io::stdout()
+        .lock()
+        .write_all(hyphenate_text(&text, lang, &format).as_bytes())?;
+    Ok(())
+}
+
+/// Whether a path is `-`, which stands for stdin or stdout.
+fn is_std(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Read a text file, or stdin for `-`.
+fn read_input(path: &Path) -> io::Result<String> {
+    if is_std(path) {
+        let mut text = String::new();
+        io::stdin().lock().read_to_string(&mut text)?;
+        Ok(text)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+/// Read a binary file, or stdin for `-`.
+fn read_bytes(path: &Path) -> io::Result<Vec<u8>> {
+    if is_std(path) {
+        let mut data = vec![];
+        io::stdin().lock().read_to_end(&mut data)?;
+        Ok(data)
+    } else {
+        fs::read(path)
+    }
+}
+
+/// Convert Latin-1 text to UTF-8. Each byte is the code point of its char.
+fn decode_latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| char::from(b)).collect()
+}
+
+/// Write data to a file, or exactly as is to stdout for `-`.
+fn write_output(path: &Path, data: &[u8]) -> io::Result<()> {
+    if is_std(path) {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(data)?;
+        stdout.flush()
+    } else {
+        fs::write(path, data)
+    }
+}
+
+/// Hyphenate the runs of letters in a text and keep everything else as is.
+fn hyphenate_text(text: &str, lang: Lang, format: &Format) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while !rest.is_empty() {
+        let split = rest.find(|c: char| !c.is_alphabetic()).unwrap_or(rest.len());
+        let (word, tail) = rest.split_at(split);
+        result.push_str(&format.hyphenate(word, lang));
+
+        let split = tail.find(char::is_alphabetic).unwrap_or(tail.len());
+        let (other, tail) = tail.split_at(split);
+        result.push_str(other);
+        rest = tail;
+    }
+    result
+}
+
+fn main() -> ExitCode {
     let cli = Cli::parse();
+    match run(&cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: &Cli) -> Result<(), Box<dyn Error>> {
     match &cli.command {
-        Some(Command::Build { file, dest }) => build_trie(file, dest),
-        Some(Command::Query { lang: code, trie, word }) => {
-            match (code, trie) {
-                (Some(code), None) => {
-                    if code.len() != 2 {
-                        return Err(
-                            format!("--lang={} is not a valid ISO code.", code).into()
-                        );
-                    }
-                    let bytes = code.as_bytes();
-                    let lang = hypher::Lang::from_iso([bytes[0], bytes[1]]).ok_or_else(
-                        || format!("--lang={} is not a valid ISO code.", code),
-                    )?;
-                    let ans = hypher::hyphenate(word, lang).join("-");
-                    println!("{}", ans);
-                    Ok(())
-                }
-                (None, Some(file)) => {
-                    let trie_data = fs::read(file)?;
-                    let lang = hypher::Lang::from_bytes(
-                        (1, 2), // TODO: what should I pick here?
-                        &trie_data,
-                    );
-                    let ans = hypher::hyphenate(word, lang).join("-");
-                    println!("{}", ans);
-                    Ok(())
-                }
-                (None, None) | (Some(_), Some(_)) => {
-                    Err(format!("must specify exactly one of `--lang` or `--trie`")
-                        .into())
-                }
-            }
+        Command::Build {
+            file,
+            dest,
+            charset,
+            encoding,
+            append,
+            stats,
+            verify,
+        } => build_trie(file, dest, *charset, encoding, *append, *stats, *verify),
+        Command::Dump { trie } => dump_trie(trie),
+        Command::Info { trie } => info(trie),
+        Command::Doctor { file } => doctor(file),
+        Command::Query { select, format, count, json, words } => {
+            query(select, format, *count, *json, words)
+        }
+        Command::Batch { select, format, file, out } => {
+            batch(select, format, file, out.as_deref())
         }
-        None => Ok(()),
+        Command::Fmt { select } => fmt(select),
     }
 }
@@ -0,0 +1,23 @@
+//! Bindings for JavaScript.
+//!
+//! This module exposes hyphenation to JavaScript through `wasm-bindgen`.
+//!
+//! Build the crate with `wasm-pack build --target web -- --features wasm` to
+//! get a package that can be imported from JavaScript.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use wasm_bindgen::prelude::*;
+
+use crate::Lang;
+
+/// Segment a word into syllables for the language with the given ISO 639-1
+/// code, like `"en"`.
+///
+/// Throws if no language with this code is available.
+#[wasm_bindgen(js_name = hyphenate)]
+pub fn hyphenate_js(word: &str, lang: &str) -> Result<Vec<String>, JsError> {
+    let lang = lang.parse::<Lang>().map_err(|err| JsError::new(&err.to_string()))?;
+    Ok(crate::hyphenate(word, lang).map(ToString::to_string).collect())
+}
@@ -0,0 +1,57 @@
+//! Checks that hyphenation doesn't allocate for short words.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use hypher::{hyphenate, hyphenate_cow, hyphenate_into, Lang};
+
+/// Counts the allocations made by the current thread.
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+fn test_no_allocations() {
+    let text = "the extensive hyphenation of wonderful words in a paragraph";
+    let mut buf = Vec::with_capacity(16);
+
+    let before = allocations();
+    for _ in 0..1000 {
+        for word in text.split(' ') {
+            hyphenate_into(word, Lang::English, &mut buf);
+            assert!(!buf.is_empty());
+            assert!(hyphenate(word, Lang::English).count() > 0);
+        }
+    }
+    assert_eq!(allocations(), before);
+
+    // Words without breaks are borrowed.
+    for word in ["the", "of", "words", "in", "a"] {
+        assert_eq!(hyphenate_cow(word, Lang::English, "-"), word);
+    }
+    assert_eq!(allocations(), before);
+
+    // Words longer than the inline buffer do allocate.
+    hyphenate_into(&"hyphenation".repeat(5), Lang::English, &mut buf);
+    assert!(allocations() > before);
+}
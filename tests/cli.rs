@@ -0,0 +1,479 @@
+#![cfg(feature = "bin")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn hypher(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_hypher"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn hypher_stdin(args: &[&str], stdin: impl AsRef<[u8]>) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_hypher"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_ref()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_batch() {
+    let output = hypher(&["batch", "--lang", "en", "tests/fixtures/batch.txt"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "The ex-ten-sive hy-phen-ation, in 2024!\n\tWon-der-ful  words.\n",
+    );
+}
+
+#[test]
+fn test_std_paths() {
+    let trie = std::env::temp_dir().join("hypher-test-std.bin");
+    let trie = trie.to_str().unwrap();
+    assert!(hypher(&["build", "tests/fixtures/dump.tex", trie]).status.success());
+    let built = std::fs::read(trie).unwrap();
+
+    // The trie is written to stdout as is.
+    let tex = std::fs::read("tests/fixtures/dump.tex").unwrap();
+    let output = hypher_stdin(&["build", "--stats", "-", "-"], &tex);
+    assert!(output.status.success());
+    assert_eq!(output.stdout, built);
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("patterns:       4\n"));
+
+    let output = hypher_stdin(&["build", "-", trie], &tex);
+    assert!(output.status.success());
+    assert_eq!(std::fs::read(trie).unwrap(), built);
+    let output = hypher(&["build", "tests/fixtures/dump.tex", "-"]);
+    assert_eq!(output.stdout, built);
+
+    let text = std::fs::read_to_string("tests/fixtures/batch.txt").unwrap();
+    let expected = "The ex-ten-sive hy-phen-ation, in 2024!\n\tWon-der-ful  words.\n";
+    for args in [
+        &["batch", "--lang", "en", "-"][..],
+        &["batch", "--out", "-", "--lang", "en", "-"],
+    ] {
+        let output = hypher_stdin(args, &text);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_dump() {
+    let trie = std::env::temp_dir().join("hypher-test-dump.bin");
+    let trie = trie.to_str().unwrap();
+    assert!(hypher(&["build", "tests/fixtures/dump.tex", trie]).status.success());
+
+    let output = hypher(&["dump", trie]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        std::fs::read_to_string("tests/fixtures/dump.tex").unwrap(),
+    );
+}
+
+#[test]
+fn test_info() {
+    let trie = std::env::temp_dir().join("hypher-test-info.bin");
+    let trie = trie.to_str().unwrap();
+    assert!(hypher(&["build", "tests/fixtures/dump.tex", trie]).status.success());
+
+    let output = hypher(&["info", trie]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("version:        2\n"));
+    assert!(
+        stdout.ends_with("lefthyphenmin:  2\nrighthyphenmin: 3\nchecksum:       no\n")
+    );
+
+    let args = ["build", "--checksum", "tests/fixtures/dump.tex", trie];
+    assert!(hypher(&args).status.success());
+    let stdout = String::from_utf8(hypher(&["info", trie]).stdout).unwrap();
+    assert!(stdout.ends_with("checksum:       yes\n"));
+}
+
+#[test]
+fn test_broken_patterns() {
+    let trie = std::env::temp_dir().join("hypher-test-broken.bin");
+    let output = hypher(&["build", "tests/fixtures/broken.tex", trie.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: line 5, column 8: level 10 out of range\n",
+    );
+}
+
+#[test]
+fn test_doctor() {
+    let output = hypher(&["doctor", "tests/fixtures/english.tex"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "no problems found\n");
+
+    // Problems in included files are found, too.
+    for file in ["tests/fixtures/doctor.tex", "tests/fixtures/input/doctor.tex"] {
+        let output = hypher(&["doctor", file]);
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "abc has no levels\n\
+             a1b is listed more than once\n\
+             exception ta-ble is listed more than once\n\
+             c3d replaces c2d with different levels\n\
+             wond-er replaces won-der with different levels\n",
+        );
+        assert_eq!(
+            String::from_utf8(output.stderr).unwrap(),
+            "error: found 5 problems\n"
+        );
+    }
+
+    let output = hypher(&["doctor", "tests/fixtures/broken.tex"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "line 5, column 8: level 10 out of range\n",
+    );
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "error: found 1 problem\n");
+
+    let tex = std::env::temp_dir().join("hypher-test-doctor.tex");
+    std::fs::write(&tex, "\\hyphenation{ as-so-ciate }").unwrap();
+    let output = hypher(&["doctor", tex.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "no patterns found, is there a `\\patterns{...}` block?\n",
+    );
+}
+
+#[test]
+fn test_conflicts() {
+    let dir = std::env::temp_dir();
+    let tex = dir.join("hypher-test-conflicts.tex");
+    let trie = dir.join("hypher-test-conflicts.bin");
+    std::fs::write(&tex, "\\patterns{ a1b 1ful a1b ab1 }").unwrap();
+
+    let output = hypher(&["build", tex.to_str().unwrap(), trie.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "warning: ab1 replaces a1b with different levels\n",
+    );
+}
+
+#[test]
+fn test_build_stats() {
+    let trie = std::env::temp_dir().join("hypher-test-stats.bin");
+    let args = ["build", "--stats", "tests/fixtures/dump.tex", trie.to_str().unwrap()];
+    let output = hypher(&args);
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("patterns:       4\nexceptions:     1\nnodes:          "));
+    assert!(stderr.contains("\nlevels:         "));
+    assert!(stderr.ends_with(" bytes\n"));
+
+    let args = ["build", "tests/fixtures/dump.tex", trie.to_str().unwrap()];
+    assert!(hypher(&args).stderr.is_empty());
+}
+
+#[test]
+fn test_build_verify() {
+    let trie = std::env::temp_dir().join("hypher-test-verify.bin");
+    let args = ["build", "--verify", "--checksum", "tests/fixtures/dump.tex"];
+    let output = hypher(&[&args[..], &[trie.to_str().unwrap()]].concat());
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    let output = hypher(&["query", "--trie", trie.to_str().unwrap(), "wonderful"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "won-der-ful\n");
+}
+
+#[test]
+fn test_build_latin1() {
+    let source = "tests/fixtures/latin1.tex";
+    let dest = std::env::temp_dir().join("hypher-test-latin1.bin");
+    let dest = dest.to_str().unwrap();
+    let output = hypher(&["build", source, dest]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("is not valid UTF-8"), "{stderr}");
+
+    assert!(hypher(&["build", "--encoding", "latin1", source, dest])
+        .status
+        .success());
+    let output = hypher(&["query", "--trie", dest, "käsebär"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "kä-se-bär\n");
+
+    let latin1 = std::fs::read(source).unwrap();
+    let output = hypher_stdin(&["build", "--encoding", "latin1", "-", "-"], latin1);
+    assert!(output.status.success());
+    assert_eq!(output.stdout, std::fs::read(dest).unwrap());
+}
+
+#[test]
+fn test_build_no_compress() {
+    let dir = std::env::temp_dir();
+    let compressed = dir.join("hypher-test-compressed.bin");
+    let uncompressed = dir.join("hypher-test-uncompressed.bin");
+    let (compressed, uncompressed) =
+        (compressed.to_str().unwrap(), uncompressed.to_str().unwrap());
+    let source = "tests/fixtures/english.tex";
+    assert!(hypher(&["build", source, compressed]).status.success());
+    let output = hypher(&["build", "--no-compress", "--stats", source, uncompressed]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let nodes = stderr.lines().find(|line| line.starts_with("nodes:")).unwrap();
+    let (before, after) = nodes["nodes:".len()..].trim().split_once(" -> ").unwrap();
+    assert_eq!(before, after);
+
+    let size = |path| std::fs::metadata(path).unwrap().len();
+    assert!(size(uncompressed) > size(compressed));
+    for trie in [compressed, uncompressed] {
+        let output = hypher(&["query", "--trie", trie, "hyphenation", "associate"]);
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "hy-phen-ation\nas-so-ciate\n"
+        );
+    }
+}
+
+#[test]
+fn test_metadata() {
+    let trie = std::env::temp_dir().join("hypher-test-metadata.bin");
+    let trie = trie.to_str().unwrap();
+    let args = ["build", "--metadata", "Test, dump.tex", "tests/fixtures/dump.tex", trie];
+    assert!(hypher(&args).status.success());
+    let stdout = String::from_utf8(hypher(&["info", trie]).stdout).unwrap();
+    assert!(stdout.ends_with("checksum:       no\nmetadata:       Test, dump.tex\n"));
+}
+
+#[test]
+fn test_garbage_trie() {
+    let trie = std::env::temp_dir().join("hypher-test-garbage.bin");
+    let trie_str = trie.to_str().unwrap();
+    let cases: [(&[u8], &str); 2] = [
+        (b"\x7fELF\x02\x01\x01\0 definitely not a trie", "not a hypher trie file"),
+        (b"HYPH\x02\0\0\x02\x03\xff\xff\xff\xffgarbage", "trie data is malformed"),
+    ];
+
+    for (data, message) in cases {
+        std::fs::write(&trie, data).unwrap();
+        let output = hypher(&["query", "--trie", trie_str, "word"]);
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(
+            String::from_utf8(output.stderr).unwrap(),
+            format!("error: {message}\n")
+        );
+    }
+}
+
+#[test]
+fn test_invalid_lang() {
+    let output = hypher(&["query", "--lang", "klingon", "word"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: --lang=klingon is neither an ISO code nor a language name\n",
+    );
+
+    let output = hypher(&["query", "--lang", "English", "wonderful"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "won-der-ful\n");
+}
+
+#[test]
+fn test_query_errors() {
+    let missing = std::env::temp_dir().join("hypher-test-missing.bin");
+    let missing = missing.to_str().unwrap();
+    let cases: [&[&str]; 5] = [
+        &["query", "--lang", "klingon", "word"],
+        &["query", "--trie", missing, "word"],
+        &["query", "word"],
+        &["query", "--lang", "en", "--trie", missing, "word"],
+        &["batch", "--lang", "en", "tests/fixtures/missing.txt"],
+    ];
+
+    for args in cases {
+        let output = hypher(args);
+        assert_eq!(output.status.code(), Some(1), "{args:?}");
+        assert!(output.stdout.is_empty());
+        assert!(String::from_utf8(output.stderr).unwrap().starts_with("error: "));
+    }
+
+    // Lines before the invalid one are still printed.
+    let output = hypher_stdin(&["query", "--lang", "en"], b"wonderful\nw\xffrd\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "won-der-ful\n");
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: line 2 of stdin is not valid UTF-8\n",
+    );
+}
+
+#[test]
+fn test_append() {
+    let trie = std::env::temp_dir().join("hypher-test-append.bin");
+    let trie = trie.to_str().unwrap();
+    assert!(hypher(&["build", "tests/fixtures/dump.tex", trie]).status.success());
+    let args = ["build", "--append", "tests/fixtures/append.tex", trie];
+    assert!(hypher(&args).status.success());
+
+    let dumped = String::from_utf8(hypher(&["dump", trie]).stdout).unwrap();
+    assert!(dumped.starts_with("\\lefthyphenmin=2\n\\righthyphenmin=3\n"));
+    assert!(dumped.contains("\n1ful\n"));
+    assert!(dumped.contains("\nx1y\n"));
+    assert!(dumped.contains("\nwon-der\n"));
+}
+
+#[test]
+fn test_query_trie() {
+    let trie = std::env::temp_dir().join("hypher-test-query.bin");
+    let trie = trie.to_str().unwrap();
+    assert!(hypher(&["build", "tests/fixtures/dump.tex", trie]).status.success());
+
+    let output = hypher(&["query", "--trie", trie, "wonderful"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "won-der-ful\n");
+
+    // The hyphenmins are read from the trie.
+    let dir = std::env::temp_dir();
+    let tex = dir.join("hypher-test-query-bounds.tex");
+    std::fs::write(&tex, "\\lefthyphenmin=1 \\righthyphenmin=1 \\patterns{ 1b }")
+        .unwrap();
+    assert!(hypher(&["build", tex.to_str().unwrap(), trie]).status.success());
+    let output = hypher(&["query", "--trie", trie, "abab"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "a-ba-b\n");
+}
+
+#[test]
+fn test_no_args() {
+    let output = hypher(&[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Usage: hypher"));
+}
+
+#[test]
+fn test_query_words() {
+    let output = hypher(&["query", "--lang", "en", "extensive", "hyphenation", "words"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "ex-ten-sive\nhy-phen-ation\nwords\n",
+    );
+}
+
+#[test]
+fn test_query_count() {
+    let output =
+        hypher(&["query", "--lang", "en", "--count", "hyphenation", "the", "extensive"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2\n0\n2\n");
+
+    let output = hypher(&["query", "--lang", "en", "--count", "--soft", "hyphenation"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_query_json() {
+    let output =
+        hypher(&["query", "--lang", "en", "--json", "--sep", "|", "hyphenation"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["word"], "hyphenation");
+    assert_eq!(json["syllables"], serde_json::json!(["hy", "phen", "ation"]));
+    assert_eq!(json["positions"], serde_json::json!([2, 6]));
+
+    let output = hypher(&["query", "--lang", "en", "--json", "say \"wonderful\""]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["word"], "say \"wonderful\"");
+    assert_eq!(json["syllables"], serde_json::json!(["say \"won", "der", "ful\""]));
+
+    let output = hypher(&["query", "--lang", "en", "--json", "--count", "hyphenation"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_fmt() {
+    let text =
+        "  The extensive hyphenation,\r\n\n\t(of “wonderful” words)--\ndon't stop \n";
+    let output = hypher_stdin(&["fmt", "--lang", "en"], text);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout,
+        "  The ex\u{ad}ten\u{ad}sive hy\u{ad}phen\u{ad}ation,\r\n\n\t\
+         (of “won\u{ad}der\u{ad}ful” words)--\ndon't stop \n",
+    );
+    assert_eq!(stdout.replace('\u{ad}', ""), text);
+
+    let output = hypher_stdin(&["fmt", "--lang", "en"], "");
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn test_query_langs() {
+    let args = ["query", "--lang", "en", "--lang", "ger", "hyphenation", "Computer"];
+    let output = hypher(&args);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "en: hy-phen-ation\nger: hy-phena-ti-on\nen: Com-puter\nger: Com-pu-ter\n",
+    );
+
+    let output =
+        hypher_stdin(&["query", "--count", "--lang", "en", "--lang", "de"], "Computer\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "en: 1\nde: 2\n");
+
+    let args = ["query", "--json", "--lang", "en", "--lang", "de", "Computer", "Tee"];
+    let output = hypher(&args);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["en"]["syllables"], serde_json::json!(["Com", "puter"]));
+    assert_eq!(lines[0]["de"]["syllables"], serde_json::json!(["Com", "pu", "ter"]));
+    assert_eq!(lines[1]["de"]["word"], "Tee");
+
+    let output =
+        hypher(&["batch", "--lang", "en", "--lang", "de", "tests/fixtures/batch.txt"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: `--lang` can only be given once for this command\n",
+    );
+}
+
+#[test]
+fn test_lang_codes() {
+    for code in ["en", "EN", "eng", "ENG", "english"] {
+        let output = hypher(&["query", "--lang", code, "wonderful"]);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "won-der-ful\n");
+    }
+
+    let output = hypher(&["query", "--lang", "ger", "Apfel"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "Ap-fel\n");
+
+    let kurdish = hypher(&["query", "--lang", "ku", "zimanekî"]);
+    assert!(kurdish.status.success());
+    for code in ["kur", "kmr"] {
+        let output = hypher(&["query", "--lang", code, "zimanekî"]);
+        assert_eq!(output.stdout, kurdish.stdout);
+    }
+
+    let output = hypher(&["query", "--lang", "xx", "word"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: --lang=xx is neither an ISO code nor a language name\n",
+    );
+}
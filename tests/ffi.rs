@@ -0,0 +1,47 @@
+#![cfg(feature = "ffi")]
+
+use std::ffi::CString;
+use std::ptr;
+
+use hypher::ffi::{hypher_hyphenate, hypher_trie_free, hypher_trie_new};
+
+#[test]
+fn test_ffi() {
+    let tex = std::fs::read_to_string("tests/fixtures/dump.tex").unwrap();
+    let bytes = hypher::builder::build_trie(&tex).unwrap();
+    let word = CString::new("wonderful").unwrap();
+    let short = CString::new("a").unwrap();
+
+    unsafe {
+        assert!(hypher_trie_new(ptr::null(), 0).is_null());
+        assert!(hypher_trie_new(b"garbage".as_ptr(), 7).is_null());
+
+        let trie = hypher_trie_new(bytes.as_ptr(), bytes.len());
+        assert!(!trie.is_null());
+        drop(bytes);
+
+        let mut out = [0; 4];
+        let count = hypher_hyphenate(trie, word.as_ptr(), out.as_mut_ptr(), 4);
+        assert_eq!(count, 2);
+        assert_eq!(out, [3, 6, 0, 0]);
+
+        // Too small buffers receive the first breaks.
+        let mut out = [0; 1];
+        let count = hypher_hyphenate(trie, word.as_ptr(), out.as_mut_ptr(), 1);
+        assert_eq!(count, 2);
+        assert_eq!(out, [3]);
+        let count = hypher_hyphenate(trie, word.as_ptr(), ptr::null_mut(), 0);
+        assert_eq!(count, 2);
+
+        let invalid = b"won\xFFderful\0";
+        let count = hypher_hyphenate(trie, invalid.as_ptr().cast(), out.as_mut_ptr(), 1);
+        assert_eq!(count, -1);
+        let count = hypher_hyphenate(ptr::null(), short.as_ptr(), out.as_mut_ptr(), 1);
+        assert_eq!(count, -1);
+        let count = hypher_hyphenate(trie, ptr::null(), out.as_mut_ptr(), 1);
+        assert_eq!(count, -1);
+
+        hypher_trie_free(trie);
+        hypher_trie_free(ptr::null_mut());
+    }
+}
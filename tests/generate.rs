@@ -11,6 +11,7 @@ struct Language {
     iso: &'static str,
     aliases: Vec<&'static str>,
     isos: Vec<&'static str>,
+    names: Vec<&'static str>,
     script: &'static str,
     tex_file: &'static str,
     lmin: u8,
@@ -55,6 +56,46 @@ const LANGS: [(&str, &str, &[&str], &str, &str, u8, u8); 35] = [
     ("Ukrainian", "uk", &[], "Cyrl", "hyph-uk.tex", 2, 2),
 ];
 
+/// The names of each language in itself, by ISO code, in addition to the
+/// English name.
+const NATIVE_NAMES: [(&str, &[&str]); 35] = [
+    ("af", &[]),
+    ("be", &["Беларуская"]),
+    ("bg", &["Български"]),
+    ("ca", &["Català"]),
+    ("cs", &["Čeština"]),
+    ("da", &["Dansk"]),
+    ("de", &["Deutsch"]),
+    ("el", &["Ελληνικά"]),
+    ("en", &[]),
+    ("es", &["Español"]),
+    ("et", &["Eesti"]),
+    ("fi", &["Suomi"]),
+    ("fr", &["Français"]),
+    ("hr", &["Hrvatski"]),
+    ("hu", &["Magyar"]),
+    ("is", &["Íslenska"]),
+    ("it", &["Italiano"]),
+    ("ka", &["ქართული"]),
+    ("ku", &["Kurmancî"]),
+    ("la", &["Latina"]),
+    ("lt", &["Lietuvių"]),
+    ("mn", &["Монгол"]),
+    ("nl", &["Nederlands"]),
+    ("no", &["Norsk", "Bokmål", "Nynorsk"]),
+    ("pl", &["Polski"]),
+    ("pt", &["Português"]),
+    ("ru", &["Русский"]),
+    ("sr", &["Српски"]),
+    ("sk", &["Slovenčina"]),
+    ("sl", &["Slovenščina"]),
+    ("sq", &["Shqip"]),
+    ("sv", &["Svenska"]),
+    ("tk", &["Türkmençe"]),
+    ("tr", &["Türkçe"]),
+    ("uk", &["Українська"]),
+];
+
 #[test]
 fn generate_code() {
     let mut languages: Vec<_> = LANGS
@@ -65,6 +106,17 @@ fn generate_code() {
             iso,
             aliases: isos.to_vec(),
             isos: std::iter::once(&iso).chain(isos).copied().collect(),
+            names: std::iter::once(name)
+                .chain(
+                    NATIVE_NAMES
+                        .iter()
+                        .find(|(code, _)| *code == iso)
+                        .unwrap()
+                        .1
+                        .iter()
+                        .copied(),
+                )
+                .collect(),
             script,
             tex_file,
             lmin,
@@ -75,10 +127,11 @@ fn generate_code() {
 
     // Build the tries.
     let mut fresh = true;
-    for Language { iso, tex_file, .. } in &languages {
+    for Language { iso, tex_file, lmin, rmin, .. } in &languages {
         let source = Path::new("patterns").join(tex_file);
         let tex = fs::read_to_string(&source).unwrap();
-        let trie = hypher::builder::build_trie(&tex);
+        let trie = hypher::builder::build_trie_with(&tex, (*lmin).into(), (*rmin).into())
+            .unwrap();
         let path = format!("tries/{iso}.bin");
         fresh &= write_check(&path, trie);
     }
@@ -134,12 +187,7 @@ fn write_lang(w: &mut String, languages: &[Language]) -> fmt::Result {
     // we include the variant for a dynamically loaded trie.
     writeln!(w, r#"    /// Dynamically loaded patterns."#)?;
     writeln!(w, r#"    #[cfg(feature = "dyn")]"#)?;
-    writeln!(w, r#"    Dyn {{"#)?;
-    writeln!(w, r#"        /// See method `bounds`."#)?;
-    writeln!(w, r#"        bounds: (usize, usize),"#)?;
-    writeln!(w, r#"        /// See method `root`."#)?;
-    writeln!(w, r#"        bytes: &'a [u8],"#)?;
-    writeln!(w, r#"    }},"#)?;
+    writeln!(w, r#"    Dyn(Trie<'a>),"#)?;
     writeln!(w, r#"    /// Cannot be constructed."#)?;
     writeln!(w, r#"    /// Silences the unused lifetime warning."#)?;
     writeln!(w, r#"    #[cfg(not(feature = "dyn"))]"#)?;
@@ -161,23 +209,144 @@ fn write_lang(w: &mut String, languages: &[Language]) -> fmt::Result {
     writeln!(w, r#"            _ => None,"#)?;
     writeln!(w, r#"        }}"#)?;
     writeln!(w, r#"    }}"#)?;
+    writeln!(w)?;
+
+    // Implementation of `from_name`, mapping English and native names to the
+    // corresponding variant.
+    writeln!(w, r#"    /// Select a language using its English or native name,"#)?;
+    writeln!(w, r#"    /// like `"German"` or `"Deutsch"`, ignoring case."#)?;
+    writeln!(w, r#"    pub fn from_name(name: &str) -> Option<Self> {{"#)?;
+    writeln!(w, r#"        let names: &[(&str, Self)] = &["#)?;
+    for Language { name, feature, names, .. } in languages {
+    for alias in names {
+    writeln!(w, r#"            #[cfg(feature = "{feature}")]"#)?;
+    writeln!(w, r#"            ("{alias}", Self::{name}),"#)?;
+    }}
+    writeln!(w, r#"        ];"#)?;
+    writeln!(w, r#"        names"#)?;
+    writeln!(w, r#"            .iter()"#)?;
+    writeln!(w, r#"            .find(|(alias, _)| eq_ignore_case(alias, name))"#)?;
+    writeln!(w, r#"            .map(|&(_, lang)| lang)"#)?;
+    writeln!(w, r#"    }}"#)?;
+    writeln!(w)?;
+
+    // Implementation of `all` and `iter`, listing the compiled-in languages.
+    writeln!(w, r#"    /// All languages whose features are enabled, in alphabetical"#)?;
+    writeln!(w, r#"    /// order of their names."#)?;
+    writeln!(w, r#"    pub fn all() -> &'static [Self] {{"#)?;
+    writeln!(w, r#"        &["#)?;
+    for Language { name, feature, .. } in languages {
+    writeln!(w, r#"            #[cfg(feature = "{feature}")]"#)?;
+    writeln!(w, r#"            Self::{name},"#)?;
+    }
+    writeln!(w, r#"        ]"#)?;
+    writeln!(w, r#"    }}"#)?;
+    writeln!(w)?;
+    writeln!(w, r#"    /// An iterator over all languages whose features are enabled."#)?;
+    writeln!(w, r#"    pub fn iter() -> impl Iterator<Item = Self> {{"#)?;
+    writeln!(w, r#"        Self::all().iter().copied()"#)?;
+    writeln!(w, r#"    }}"#)?;
     writeln!(w, r#"}}"#)?;
     writeln!(w)?;
 
     // Implementation of `from_bytes`, creating a dynamic language from raw data.
     writeln!(w, r#"impl<'a> Lang<'a> {{"#)?;
     writeln!(w, r#"    /// Dynamically load new patterns."#)?;
-    writeln!(w, r#"    /// No validation will occur here: if you provide a malformed"#)?;
-    writeln!(w, r#"    /// automata the program might panic when you try to use it."#)?;
+    writeln!(w, r#"    ///"#)?;
+    writeln!(w, r#"    /// This is a shorthand for wrapping `Trie::from_bytes`."#)?;
+    writeln!(w, r#"    ///"#)?;
+    writeln!(w, r#"    /// Pass as `bytes` the output of `hypher::builder::build_trie`"#)?;
+    writeln!(w, r#"    /// or an equivalently obtained well-formed trie. Its stored"#)?;
+    writeln!(w, r#"    /// (left,right)-hyphenmin become the language's `bounds`."#)?;
+    writeln!(w, r#"    ///"#)?;
+    writeln!(w, r#"    /// Fails if `bytes` don't start with the header of a trie of"#)?;
+    writeln!(w, r#"    /// a supported format version or if its metadata or root node"#)?;
+    writeln!(w, r#"    /// can't be decoded. No further validation will occur here: if"#)?;
+    writeln!(w, r#"    /// you provide a malformed automata, hyphenation won't panic"#)?;
+    writeln!(w, r#"    /// but may yield nonsensical syllables."#)?;
+    writeln!(w, r#"    ///"#)?;
+    writeln!(w, r#"    /// Tries built by hypher 0.1 have no header and the bounds"#)?;
+    writeln!(w, r#"    /// had to be passed here instead. Rebuild them from their"#)?;
+    writeln!(w, r#"    /// pattern file, with `hypher::builder::build_trie_with`"#)?;
+    writeln!(w, r#"    /// to store those bounds in the trie."#)?;
+    writeln!(w, r#"    #[cfg(feature = "dyn")]"#)?;
+    writeln!(w, r#"    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, TrieError> {{"#)?;
+    writeln!(w, r#"        Trie::from_bytes(bytes).map(Self::Dyn)"#)?;
+    writeln!(w, r#"    }}"#)?;
+    writeln!(w)?;
+
+    // Implementation of `iso`, the inverse of `from_iso`.
+    writeln!(w, r#"    /// The ISO 639-1 code of the language."#)?;
+    writeln!(w, r#"    ///"#)?;
+    writeln!(w, r#"    /// Returns `None` for dynamically loaded patterns. For"#)?;
+    writeln!(w, r#"    /// languages with aliases, this is the main code."#)?;
+    writeln!(w, r#"    pub fn iso(&self) -> Option<[u8; 2]> {{"#)?;
+    writeln!(w, r#"        match self {{"#)?;
+    for Language { name, feature, iso, .. } in languages {
+    writeln!(w, r#"            #[cfg(feature = "{feature}")]"#)?;
+    writeln!(w, r#"            Self::{name} => Some(*b"{iso}"),"#)?;
+    }
+    writeln!(w, r#"            #[cfg(feature = "dyn")]"#)?;
+    writeln!(w, r#"            Self::Dyn(_) => None,"#)?;
+    writeln!(w, r#"            #[cfg(not(feature = "dyn"))]"#)?;
+    writeln!(w, r#"            Self::Absurd(_) => unreachable!(),"#)?;
+    writeln!(w, r#"        }}"#)?;
+    writeln!(w, r#"    }}"#)?;
+    writeln!(w)?;
+
+    // Implementation of `name`, the inverse of `from_name`.
+    writeln!(w, r#"    /// The English name of the language."#)?;
+    writeln!(w, r#"    ///"#)?;
+    writeln!(w, r#"    /// Returns `None` for dynamically loaded patterns."#)?;
+    writeln!(w, r#"    pub fn name(&self) -> Option<&'static str> {{"#)?;
+    writeln!(w, r#"        match self {{"#)?;
+    for Language { name, feature, .. } in languages {
+    writeln!(w, r#"            #[cfg(feature = "{feature}")]"#)?;
+    writeln!(w, r#"            Self::{name} => Some("{name}"),"#)?;
+    }
+    writeln!(w, r#"            #[cfg(feature = "dyn")]"#)?;
+    writeln!(w, r#"            Self::Dyn(_) => None,"#)?;
+    writeln!(w, r#"            #[cfg(not(feature = "dyn"))]"#)?;
+    writeln!(w, r#"            Self::Absurd(_) => unreachable!(),"#)?;
+    writeln!(w, r#"        }}"#)?;
+    writeln!(w, r#"    }}"#)?;
+    writeln!(w)?;
+
+    // Implementation of `script`, mapping the ISO 15924 code to the variant
+    // of the `Script` enum.
+    writeln!(w, r#"    /// The script the language is written in."#)?;
+    writeln!(w, r#"    ///"#)?;
+    writeln!(w, r#"    /// Returns `None` for dynamically loaded patterns."#)?;
+    writeln!(w, r#"    pub fn script(&self) -> Option<Script> {{"#)?;
+    writeln!(w, r#"        match self {{"#)?;
+    for Language { name, feature, script, .. } in languages {
+    let script = match *script {
+        "Latn" => "Latin",
+        "Cyrl" => "Cyrillic",
+        "Grek" => "Greek",
+        "Geor" => "Georgian",
+        _ => panic!("unknown script {script}"),
+    };
+    writeln!(w, r#"            #[cfg(feature = "{feature}")]"#)?;
+    writeln!(w, r#"            Self::{name} => Some(Script::{script}),"#)?;
+    }
+    writeln!(w, r#"            #[cfg(feature = "dyn")]"#)?;
+    writeln!(w, r#"            Self::Dyn(_) => None,"#)?;
+    writeln!(w, r#"            #[cfg(not(feature = "dyn"))]"#)?;
+    writeln!(w, r#"            Self::Absurd(_) => unreachable!(),"#)?;
+    writeln!(w, r#"        }}"#)?;
+    writeln!(w, r#"    }}"#)?;
+    writeln!(w)?;
+
+    // Implementation of `from_bytes_checked`, verifying the trie's checksum.
+    writeln!(w, r#"    /// Dynamically load new patterns, verifying their checksum."#)?;
+    writeln!(w, r#"    ///"#)?;
+    writeln!(w, r#"    /// Like `from_bytes`, but also fails if the trie doesn't"#)?;
+    writeln!(w, r#"    /// end with a checksum or it doesn't match the data. Build"#)?;
+    writeln!(w, r#"    /// the trie with `TrieBuilder::set_checksum` to add one."#)?;
     writeln!(w, r#"    #[cfg(feature = "dyn")]"#)?;
-    writeln!(w, r#"    pub fn from_bytes("#)?;
-    writeln!(w, r#"        /// (left,right)-hyphenmin of the language."#)?;
-    writeln!(w, r#"        bounds: (usize, usize),"#)?;
-    writeln!(w, r#"        /// Pass to this the output of `hypher::builder::build_trie`"#)?;
-    writeln!(w, r#"        /// or an equivalently obtained well-formed trie."#)?;
-    writeln!(w, r#"        bytes: &'a [u8],"#)?;
-    writeln!(w, r#"    ) -> Self {{"#)?;
-    writeln!(w, r#"        Self::Dyn {{ bounds, bytes }}"#)?;
+    writeln!(w, r#"    pub fn from_bytes_checked(bytes: &'a [u8]) -> Result<Self, TrieError> {{"#)?;
+    writeln!(w, r#"        Trie::from_bytes_checked(bytes).map(Self::Dyn)"#)?;
     writeln!(w, r#"    }}"#)?;
     writeln!(w)?;
 
@@ -185,7 +354,17 @@ fn write_lang(w: &mut String, languages: &[Language]) -> fmt::Result {
     writeln!(w, r#"    /// The default number of chars to each side between"#)?;
     writeln!(w, r#"    /// which breaking is forbidden."#)?;
     writeln!(w, r#"    ///"#)?;
-    writeln!(w, r#"    /// This follows typographic conventions."#)?;
+    writeln!(w, r#"    /// This follows typographic conventions. [`hyphenate`] uses"#)?;
+    writeln!(w, r#"    /// these bounds, [`hyphenate_bounded`] overrides them. For"#)?;
+    writeln!(w, r#"    /// dynamically loaded patterns, they are the"#)?;
+    writeln!(w, r#"    /// (left,right)-hyphenmin stored in the trie. For the"#)?;
+    writeln!(w, r#"    /// built-in languages, they are:"#)?;
+    writeln!(w, r#"    ///"#)?;
+    writeln!(w, r#"    /// | Language | Left | Right |"#)?;
+    writeln!(w, r#"    /// |----------|------|-------|"#)?;
+    for Language { name, lmin, rmin, .. } in languages {
+    writeln!(w, r#"    /// | {name} | {lmin} | {rmin} |"#)?;
+    }
     writeln!(w, r#"    pub fn bounds(self) -> (usize, usize) {{"#)?;
     writeln!(w, r#"        match self {{"#)?;
     for Language { name, feature, lmin, rmin, .. } in languages {
@@ -193,27 +372,26 @@ fn write_lang(w: &mut String, languages: &[Language]) -> fmt::Result {
     writeln!(w, r#"            Self::{name} => ({lmin}, {rmin}),"#)?;
     }
     writeln!(w, r#"            #[cfg(feature = "dyn")]"#)?;
-    writeln!(w, r#"            Self::Dyn {{ bounds, .. }} => bounds,"#)?;
+    writeln!(w, r#"            Self::Dyn(trie) => trie.bounds(),"#)?;
     writeln!(w, r#"            #[cfg(not(feature = "dyn"))]"#)?;
     writeln!(w, r#"            Self::Absurd(_) => unreachable!(),"#)?;
     writeln!(w, r#"        }}"#)?;
     writeln!(w, r#"    }}"#)?;
     writeln!(w)?;
 
-    // Implementation of `root`, initializing the entry point of the automata.
-    writeln!(w, r#"    /// Entry point of the automata."#)?;
-    writeln!(w, r#"    fn root(self) -> State<'a> {{"#)?;
+    // Implementation of `trie`, giving access to the language's automata.
+    writeln!(w, r#"    /// The trie with the patterns of the language."#)?;
+    writeln!(w, r#"    pub fn trie(self) -> Trie<'a> {{"#)?;
     writeln!(w, r#"        match self {{"#)?;
     for Language { name, feature, iso, .. } in languages {
     writeln!(w, r#"            #[cfg(feature = "{feature}")]"#)?;
-    writeln!(w, r#"            Self::{name} => State::root(include_bytes!("../tries/{iso}.bin")),"#)?;
+    writeln!(w, r#"            Self::{name} => Trie {{ data: include_bytes!("../tries/{iso}.bin") }},"#)?;
     }
     writeln!(w, r#"            #[cfg(feature = "dyn")]"#)?;
-    writeln!(w, r#"            Self::Dyn {{ bytes, .. }} => State::root(bytes),"#)?;
+    writeln!(w, r#"            Self::Dyn(trie) => trie,"#)?;
     writeln!(w, r#"            #[cfg(not(feature = "dyn"))]"#)?;
     writeln!(w, r#"            Self::Absurd(_) => unreachable!(),"#)?;
     writeln!(w, r#"        }}"#)?;
     writeln!(w, r#"    }}"#)?;
     writeln!(w, r#"}}"#)
 }
-
@@ -0,0 +1,29 @@
+//! Checks the whole pipeline from pattern file to hyphenated words.
+
+use hypher::builder::TrieBuilder;
+use hypher::{hyphenate, Lang};
+
+/// Words and how TeX hyphenates them with the US English patterns.
+const WORDS: &[&str] =
+    &["hy-phen-ation", "ex-am-ple", "com-puter", "al-go-rithm", "as-so-ciate"];
+
+#[test]
+fn test_pipeline() {
+    let tex = std::fs::read_to_string("tests/fixtures/english.tex").unwrap();
+    let mut builder = TrieBuilder::new();
+    builder.insert_tex(&tex).unwrap();
+    builder.set_checksum(true);
+    let uncompressed = builder.encode().unwrap();
+    builder.compress();
+    let compressed = builder.encode().unwrap();
+    assert!(compressed.len() < uncompressed.len());
+
+    for bytes in [&uncompressed, &compressed] {
+        let lang = Lang::from_bytes_checked(bytes).unwrap();
+        for expected in WORDS {
+            let word = expected.replace('-', "");
+            assert_eq!(hyphenate(&word, lang).join("-"), *expected, "built");
+            assert_eq!(hyphenate(&word, Lang::English).join("-"), *expected, "bundled");
+        }
+    }
+}
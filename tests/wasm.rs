@@ -0,0 +1,11 @@
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use hypher::wasm::hyphenate_js;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn test_hyphenate_js() {
+    let syllables = hyphenate_js("hyphenation", "en").ok().unwrap();
+    assert_eq!(syllables, ["hy", "phen", "ation"]);
+    assert!(hyphenate_js("hyphenation", "xx").is_err());
+}